@@ -0,0 +1 @@
+ALTER TABLE chats ADD COLUMN allow_anonymous BOOLEAN NOT NULL DEFAULT FALSE;
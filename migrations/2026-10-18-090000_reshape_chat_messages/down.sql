@@ -0,0 +1,9 @@
+DROP INDEX chat_messages_chat_id_creation_date_idx;
+
+ALTER TABLE chat_messages DROP COLUMN sender_uuid;
+DELETE FROM chat_messages WHERE creator_id IS NULL;
+ALTER TABLE chat_messages ALTER COLUMN creator_id SET NOT NULL;
+
+ALTER TABLE chat_messages DROP COLUMN iv;
+ALTER TABLE chat_messages DROP COLUMN cipher;
+ALTER TABLE chat_messages ADD COLUMN text VARCHAR NOT NULL DEFAULT '';
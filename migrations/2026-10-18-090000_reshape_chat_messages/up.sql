@@ -0,0 +1,14 @@
+-- Messages are end to end encrypted: only the cipher and its iv are stored, never any plain text
+ALTER TABLE chat_messages DROP COLUMN text;
+ALTER TABLE chat_messages ADD COLUMN cipher TEXT NOT NULL DEFAULT '';
+ALTER TABLE chat_messages ADD COLUMN iv VARCHAR NOT NULL DEFAULT '';
+ALTER TABLE chat_messages ALTER COLUMN cipher DROP DEFAULT;
+ALTER TABLE chat_messages ALTER COLUMN iv DROP DEFAULT;
+
+-- Senders of anonymous chats are no registered users, so the uuid of the socket user is the reference
+ALTER TABLE chat_messages ALTER COLUMN creator_id DROP NOT NULL;
+ALTER TABLE chat_messages ADD COLUMN sender_uuid UUID;
+UPDATE chat_messages SET sender_uuid = users.uuid FROM users WHERE users.id = chat_messages.creator_id;
+ALTER TABLE chat_messages ALTER COLUMN sender_uuid SET NOT NULL;
+
+CREATE INDEX chat_messages_chat_id_creation_date_idx ON chat_messages (chat_id, creation_date, id);
@@ -0,0 +1,17 @@
+-- Members are referenced by uuid: anonymous guests of a chat aren't registered users
+CREATE TABLE chat_members (
+    id SERIAL PRIMARY KEY,
+    chat_id INT NOT NULL,
+    user_uuid UUID NOT NULL,
+    role VARCHAR NOT NULL DEFAULT 'Member',
+    creation_date TIMESTAMP NOT NULL DEFAULT NOW(),
+    modification_date TIMESTAMP
+);
+
+ALTER TABLE chat_members ADD CONSTRAINT chat_id_fkey FOREIGN KEY (chat_id) REFERENCES chats(id);
+ALTER TABLE chat_members ADD CONSTRAINT chat_id_user_uuid_unique UNIQUE (chat_id, user_uuid);
+ALTER TABLE chat_members ADD CONSTRAINT role_values CHECK (role IN ('Owner', 'Admin', 'Member', 'ReadOnly'));
+
+-- Creators of existing chats become their owners
+INSERT INTO chat_members (chat_id, user_uuid, role)
+SELECT chats.id, users.uuid, 'Owner' FROM chats JOIN users ON users.id = chats.creator_id;
@@ -0,0 +1,3 @@
+ALTER TABLE chats DROP COLUMN share_uri_uses;
+ALTER TABLE chats DROP COLUMN share_uri_max_uses;
+ALTER TABLE chats DROP COLUMN share_uri_expiration_date;
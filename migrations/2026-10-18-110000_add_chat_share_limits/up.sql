@@ -0,0 +1,3 @@
+ALTER TABLE chats ADD COLUMN share_uri_expiration_date TIMESTAMP NULL;
+ALTER TABLE chats ADD COLUMN share_uri_max_uses INTEGER NULL CHECK (share_uri_max_uses > 0);
+ALTER TABLE chats ADD COLUMN share_uri_uses INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+DROP TABLE chat_group_keys;
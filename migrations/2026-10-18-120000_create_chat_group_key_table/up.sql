@@ -0,0 +1,13 @@
+-- Latest group key per recipient, still wrapped with the recipient's public key
+CREATE TABLE chat_group_keys (
+    id SERIAL PRIMARY KEY,
+    chat_id INT NOT NULL,
+    user_uuid UUID NOT NULL,
+    from_user_uuid UUID NOT NULL,
+    encrypted_key TEXT NOT NULL,
+    iv VARCHAR NOT NULL,
+    creation_date TIMESTAMP NOT NULL
+);
+
+ALTER TABLE chat_group_keys ADD CONSTRAINT chat_group_keys_chat_id_fkey FOREIGN KEY (chat_id) REFERENCES chats(id);
+ALTER TABLE chat_group_keys ADD CONSTRAINT chat_group_keys_chat_id_user_uuid_unique UNIQUE (chat_id, user_uuid);
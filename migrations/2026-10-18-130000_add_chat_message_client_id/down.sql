@@ -0,0 +1,2 @@
+ALTER TABLE chat_messages DROP CONSTRAINT chat_messages_chat_id_sender_uuid_client_id_unique;
+ALTER TABLE chat_messages DROP COLUMN client_id;
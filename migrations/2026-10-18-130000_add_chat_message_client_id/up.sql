@@ -0,0 +1,3 @@
+-- Idempotency key of the sender: resent messages must not be stored twice
+ALTER TABLE chat_messages ADD COLUMN client_id VARCHAR NULL;
+ALTER TABLE chat_messages ADD CONSTRAINT chat_messages_chat_id_sender_uuid_client_id_unique UNIQUE (chat_id, sender_uuid, client_id);
@@ -0,0 +1,2 @@
+ALTER TABLE chat_members DROP COLUMN last_read_date;
+ALTER TABLE chat_members DROP COLUMN last_read_message_uuid;
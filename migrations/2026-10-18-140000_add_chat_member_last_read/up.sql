@@ -0,0 +1,3 @@
+-- Read pointer per member: the latest message they confirmed reading
+ALTER TABLE chat_members ADD COLUMN last_read_message_uuid UUID NULL;
+ALTER TABLE chat_members ADD COLUMN last_read_date TIMESTAMP NULL;
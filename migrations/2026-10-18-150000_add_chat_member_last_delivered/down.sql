@@ -0,0 +1,2 @@
+ALTER TABLE chat_members DROP COLUMN last_delivered_date;
+ALTER TABLE chat_members DROP COLUMN last_delivered_message_uuid;
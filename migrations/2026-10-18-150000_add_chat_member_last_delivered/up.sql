@@ -0,0 +1,3 @@
+-- Delivery pointer per member: the latest message they confirmed receiving, replaying starts right after it
+ALTER TABLE chat_members ADD COLUMN last_delivered_message_uuid UUID NULL;
+ALTER TABLE chat_members ADD COLUMN last_delivered_date TIMESTAMP NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE chats DROP COLUMN default_ttl;
+
+DROP INDEX chat_messages_expiration_date_index;
+ALTER TABLE chat_messages DROP COLUMN burn_after_read;
+ALTER TABLE chat_messages DROP COLUMN expiration_date;
@@ -0,0 +1,7 @@
+-- Self-destructing messages: purged once expired, burned after the first read if flagged so
+ALTER TABLE chat_messages ADD COLUMN expiration_date TIMESTAMP NULL;
+ALTER TABLE chat_messages ADD COLUMN burn_after_read BOOLEAN NOT NULL DEFAULT FALSE;
+CREATE INDEX chat_messages_expiration_date_index ON chat_messages (expiration_date) WHERE expiration_date IS NOT NULL;
+
+-- Seconds messages live for if the sender didn't choose a TTL themselves
+ALTER TABLE chats ADD COLUMN default_ttl INTEGER NULL CHECK (default_ttl > 0);
@@ -0,0 +1,6 @@
+DROP TABLE purge_records;
+
+ALTER TABLE chats DROP CONSTRAINT chats_retention_days_values;
+ALTER TABLE chats DROP CONSTRAINT chats_retention_policy_values;
+ALTER TABLE chats DROP COLUMN retention_days;
+ALTER TABLE chats DROP COLUMN retention_policy;
@@ -0,0 +1,22 @@
+-- Retention of a chat's messages: kept 'Forever', purged after 'Days' (retention_days) or once the room is empty again
+ALTER TABLE chats ADD COLUMN retention_policy VARCHAR NOT NULL DEFAULT 'Forever';
+ALTER TABLE chats ADD COLUMN retention_days INTEGER NULL;
+ALTER TABLE chats ADD CONSTRAINT chats_retention_policy_values CHECK (retention_policy IN ('Forever', 'Days', 'DeleteOnEmpty'));
+ALTER TABLE chats ADD CONSTRAINT chats_retention_days_values CHECK (
+    (retention_policy = 'Days' AND retention_days > 0) OR (retention_policy <> 'Days' AND retention_days IS NULL)
+);
+
+-- Proof of what got deleted for good, when and why - only references, never any content
+CREATE TABLE purge_records (
+    id SERIAL PRIMARY KEY,
+    chat_uuid UUID NOT NULL,
+    reason VARCHAR NOT NULL,
+    message_uuids UUID[] NOT NULL,
+    message_count INTEGER NOT NULL,
+    creation_date TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE purge_records ADD CONSTRAINT purge_records_reason_values CHECK (
+    reason IN ('Expired', 'BurnedAfterRead', 'Retention', 'DeleteOnEmpty', 'ChatDeleted')
+);
+CREATE INDEX purge_records_chat_uuid_index ON purge_records (chat_uuid);
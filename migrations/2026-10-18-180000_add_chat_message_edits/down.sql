@@ -0,0 +1,2 @@
+ALTER TABLE chat_messages DROP COLUMN deletion_date;
+ALTER TABLE chat_messages DROP COLUMN modification_date;
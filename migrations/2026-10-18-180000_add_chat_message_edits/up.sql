@@ -0,0 +1,3 @@
+-- Edited messages keep their position, retracted ones only leave a tombstone behind (their cipher gets wiped)
+ALTER TABLE chat_messages ADD COLUMN modification_date TIMESTAMP NULL;
+ALTER TABLE chat_messages ADD COLUMN deletion_date TIMESTAMP NULL;
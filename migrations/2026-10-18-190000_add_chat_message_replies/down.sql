@@ -0,0 +1,2 @@
+ALTER TABLE chat_messages DROP CONSTRAINT chat_messages_reply_to_fkey;
+ALTER TABLE chat_messages DROP COLUMN reply_to;
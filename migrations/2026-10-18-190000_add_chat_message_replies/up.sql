@@ -0,0 +1,4 @@
+-- Replies reference their parent message - a purged parent only leaves the reply without reference.
+-- That the parent belongs to the very same chat is checked when storing the reply.
+ALTER TABLE chat_messages ADD COLUMN reply_to UUID NULL;
+ALTER TABLE chat_messages ADD CONSTRAINT chat_messages_reply_to_fkey FOREIGN KEY (reply_to) REFERENCES chat_messages (uuid) ON DELETE SET NULL;
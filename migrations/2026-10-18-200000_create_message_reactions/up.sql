@@ -0,0 +1,11 @@
+-- Reactions are plain metadata (not encrypted) - one per user and emoji, gone along with their message
+CREATE TABLE message_reactions (
+    id SERIAL PRIMARY KEY,
+    chat_message_id INT NOT NULL,
+    user_uuid UUID NOT NULL,
+    emoji VARCHAR NOT NULL,
+    creation_date TIMESTAMP NOT NULL
+);
+
+ALTER TABLE message_reactions ADD CONSTRAINT message_reactions_chat_message_id_fkey FOREIGN KEY (chat_message_id) REFERENCES chat_messages(id) ON DELETE CASCADE;
+ALTER TABLE message_reactions ADD CONSTRAINT message_reactions_chat_message_id_user_uuid_emoji_unique UNIQUE (chat_message_id, user_uuid, emoji);
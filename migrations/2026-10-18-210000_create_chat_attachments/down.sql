@@ -0,0 +1,2 @@
+ALTER TABLE purge_records DROP COLUMN attachment_uuids;
+DROP TABLE chat_attachments;
@@ -0,0 +1,18 @@
+-- Encrypted blobs shared in a chat - only their metadata lives here, the ciphertext is kept in the blob store
+CREATE TABLE chat_attachments (
+    id SERIAL PRIMARY KEY,
+    chat_id INT NOT NULL,
+    uuid UUID NOT NULL,
+    uploader_uuid UUID NOT NULL,
+    size BIGINT NOT NULL,
+    content_hash VARCHAR NOT NULL,
+    creation_date TIMESTAMP NOT NULL,
+    -- Taken from the default TTL of the chat at upload, expired attachments are purged like expired messages
+    expiration_date TIMESTAMP NULL
+);
+
+ALTER TABLE chat_attachments ADD CONSTRAINT chat_attachments_chat_id_fkey FOREIGN KEY (chat_id) REFERENCES chats(id);
+ALTER TABLE chat_attachments ADD CONSTRAINT chat_attachments_uuid_unique UNIQUE (uuid);
+
+-- Purged attachments are recorded along with the messages
+ALTER TABLE purge_records ADD COLUMN attachment_uuids UUID[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,98 @@
+use crate::blob_store::BlobStore;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use tokio::io::AsyncRead;
+use uuid::Uuid;
+
+/**
+ * `BlobStore` on the local disk - one directory per chat below `ATTACHMENT_DIR`, one file per blob
+ */
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub const fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn chat_dir(&self, chat_uuid: Uuid) -> PathBuf {
+        self.root.join(chat_uuid.to_string())
+    }
+
+    fn path(&self, chat_uuid: Uuid, uuid: Uuid) -> PathBuf {
+        self.chat_dir(chat_uuid).join(uuid.to_string())
+    }
+}
+
+impl BlobStore for LocalBlobStore {
+    fn append(&self, chat_uuid: Uuid, uuid: Uuid, chunk: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(self.chat_dir(chat_uuid))?;
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path(chat_uuid, uuid))?
+            .write_all(chunk)
+    }
+
+    fn open(&self, chat_uuid: Uuid, uuid: Uuid) -> io::Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let file = File::open(self.path(chat_uuid, uuid))?;
+
+        Ok(Box::new(tokio::fs::File::from_std(file)))
+    }
+
+    fn delete(&self, chat_uuid: Uuid, uuid: Uuid) -> io::Result<()> {
+        fs::remove_file(self.path(chat_uuid, uuid))
+    }
+
+    fn delete_chat(&self, chat_uuid: Uuid) -> io::Result<()> {
+        match fs::remove_dir_all(self.chat_dir(chat_uuid)) {
+            // Chats without attachments never got a directory
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LocalBlobStore;
+    use crate::blob_store::BlobStore;
+    use futures::StreamExt;
+    use std::{env, io};
+    use tokio_util::io::ReaderStream;
+    use uuid::Uuid;
+
+    async fn read(store: &LocalBlobStore, chat_uuid: Uuid, uuid: Uuid) -> io::Result<Vec<u8>> {
+        let mut stream = ReaderStream::new(store.open(chat_uuid, uuid)?);
+        let mut blob = Vec::new();
+
+        while let Some(chunk) = stream.next().await {
+            blob.extend_from_slice(&chunk?);
+        }
+
+        Ok(blob)
+    }
+
+    #[actix_rt::test]
+    async fn append_read_and_delete() {
+        let store = LocalBlobStore::new(env::temp_dir().join(format!("skumb-blobs-{}", Uuid::new_v4())));
+        let (chat_uuid, uuid) = (Uuid::new_v4(), Uuid::new_v4());
+
+        store.append(chat_uuid, uuid, b"cipher").unwrap();
+        store.append(chat_uuid, uuid, b"text").unwrap();
+        assert_eq!(read(&store, chat_uuid, uuid).await.unwrap(), b"ciphertext".to_vec());
+
+        store.delete(chat_uuid, uuid).unwrap();
+        assert!(read(&store, chat_uuid, uuid).await.is_err());
+
+        store.append(chat_uuid, uuid, b"again").unwrap();
+        store.delete_chat(chat_uuid).unwrap();
+        assert!(read(&store, chat_uuid, uuid).await.is_err());
+
+        // Nothing left to delete is fine
+        store.delete_chat(Uuid::new_v4()).unwrap();
+    }
+}
@@ -0,0 +1,28 @@
+pub mod local;
+
+use std::io;
+use tokio::io::AsyncRead;
+use uuid::Uuid;
+
+/**
+ * Keeps the (already encrypted) ciphertext of chat attachments - opaque bytes grouped per chat.
+ * The server never looks into them, so any store able to append, read and delete bytes works.
+ */
+pub trait BlobStore: Send + Sync {
+    /**
+     * Appends a chunk to the blob, creating it with the first one
+     */
+    fn append(&self, chat_uuid: Uuid, uuid: Uuid, chunk: &[u8]) -> io::Result<()>;
+
+    /**
+     * Opens the blob for reading, so it can be streamed instead of being loaded into memory as a whole
+     */
+    fn open(&self, chat_uuid: Uuid, uuid: Uuid) -> io::Result<Box<dyn AsyncRead + Send + Unpin>>;
+
+    fn delete(&self, chat_uuid: Uuid, uuid: Uuid) -> io::Result<()>;
+
+    /**
+     * Deletes all blobs of the chat - once it's gone for good
+     */
+    fn delete_chat(&self, chat_uuid: Uuid) -> io::Result<()>;
+}
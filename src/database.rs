@@ -33,17 +33,29 @@ pub struct DB {
     pub connection: PooledConnection<ConnectionManager<PgConnection>>,
 }
 
-impl FromRequest for DB {
-    type Error = Error;
-    type Future = Ready<Result<Self, Error>>;
-    fn from_request(_: &HttpRequest, _: &mut Payload) -> Self::Future {
+impl DB {
+    /**
+     * Fetches a pooled connection outside of requests (e.g. from within actors or blocking tasks)
+     */
+    pub fn acquire() -> Option<Self> {
         match DBCONNECTION.db_connection.get() {
-            Ok(connection) => ok(Self { connection }),
+            Ok(connection) => Some(Self { connection }),
             Err(error) => {
                 sentry::capture_error(&error);
 
-                err(ErrorServiceUnavailable("could not make connection to database"))
+                None
             }
         }
     }
 }
+
+impl FromRequest for DB {
+    type Error = Error;
+    type Future = Ready<Result<Self, Error>>;
+    fn from_request(_: &HttpRequest, _: &mut Payload) -> Self::Future {
+        Self::acquire().map_or_else(
+            || err(ErrorServiceUnavailable("could not make connection to database")),
+            ok,
+        )
+    }
+}
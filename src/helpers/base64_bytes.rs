@@ -0,0 +1,90 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+use std::fmt;
+
+/**
+ * Serde helper for ciphertexts: base64 strings for JSON, raw bytes for binary formats like `MessagePack`.
+ * Keeps the stored (and JSON) representation base64, while binary frames save its overhead.
+ * Anything that isn't valid base64 is left as string.
+ */
+pub fn serialize<S: Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        return serializer.serialize_str(value);
+    }
+
+    match STANDARD.decode(value) {
+        Ok(bytes) => serializer.serialize_bytes(&bytes),
+        Err(_) => serializer.serialize_str(value),
+    }
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    deserializer.deserialize_any(Base64Visitor)
+}
+
+struct Base64Visitor;
+
+impl Visitor<'_> for Base64Visitor {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a base64 string or bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(value.to_string())
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Self::Value, E> {
+        Ok(value)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+        Ok(STANDARD.encode(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+    struct Cipher {
+        #[serde(with = "super")]
+        cipher: String,
+    }
+
+    #[test]
+    fn json_keeps_base64() {
+        let cipher = Cipher {
+            cipher: "AAEC".to_string(),
+        };
+
+        assert_eq!(serde_json::to_string(&cipher).unwrap(), r#"{"cipher":"AAEC"}"#);
+        assert_eq!(serde_json::from_str::<Cipher>(r#"{"cipher":"AAEC"}"#).unwrap(), cipher);
+    }
+
+    #[test]
+    fn binary_uses_bytes() {
+        let cipher = Cipher {
+            cipher: "AAEC".to_string(),
+        };
+        let packed = rmp_serde::to_vec_named(&cipher).unwrap();
+
+        // bin 8 with the 3 decoded bytes instead of the 4 characters
+        assert!(packed.ends_with(&[0xc4, 3, 0, 1, 2]));
+        assert_eq!(rmp_serde::from_slice::<Cipher>(&packed).unwrap(), cipher);
+    }
+
+    #[test]
+    fn binary_keeps_invalid_base64() {
+        let cipher = Cipher {
+            cipher: "not base64!".to_string(),
+        };
+        let packed = rmp_serde::to_vec_named(&cipher).unwrap();
+
+        assert_eq!(rmp_serde::from_slice::<Cipher>(&packed).unwrap(), cipher);
+    }
+}
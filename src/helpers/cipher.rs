@@ -0,0 +1,23 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// Bytes of the IVs the clients use with AES-GCM
+pub const AES_GCM_IV_LENGTH: usize = 12;
+
+pub fn is_aes_gcm_iv(iv: &str) -> bool {
+    STANDARD.decode(iv).is_ok_and(|bytes| bytes.len() == AES_GCM_IV_LENGTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_aes_gcm_iv;
+
+    #[test]
+    fn test_is_aes_gcm_iv() {
+        assert!(is_aes_gcm_iv("AAECAwQFBgcICQoL"));
+        assert!(!is_aes_gcm_iv("AAECAwQFBgcICQ=="));
+        assert!(!is_aes_gcm_iv("AAECAwQFBgcICQoLDA=="));
+        assert!(!is_aes_gcm_iv("iv123"));
+        assert!(!is_aes_gcm_iv(""));
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/**
+ * Serde helper for partial updates telling a left out field (`None`) and an explicit `null` (`Some(None)`) apart.
+ * Meant to be combined with `#[serde(default, skip_serializing_if = "Option::is_none")]`.
+ */
+#[allow(clippy::option_option, clippy::ref_option)]
+pub fn serialize<T: Serialize, S: Serializer>(value: &Option<Option<T>>, serializer: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(inner) => inner.serialize(serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[allow(clippy::option_option)]
+pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Option<T>>, D::Error> {
+    Option::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+    struct Patch {
+        #[serde(default, skip_serializing_if = "Option::is_none", with = "super")]
+        ttl: Option<Option<i32>>,
+    }
+
+    #[test]
+    fn absent_null_and_value() {
+        assert_eq!(serde_json::from_str::<Patch>("{}").unwrap(), Patch { ttl: None });
+        assert_eq!(
+            serde_json::from_str::<Patch>(r#"{"ttl":null}"#).unwrap(),
+            Patch { ttl: Some(None) }
+        );
+        assert_eq!(
+            serde_json::from_str::<Patch>(r#"{"ttl":60}"#).unwrap(),
+            Patch { ttl: Some(Some(60)) }
+        );
+    }
+
+    #[test]
+    fn serialize_skips_absent() {
+        assert_eq!(serde_json::to_string(&Patch { ttl: None }).unwrap(), "{}");
+        assert_eq!(
+            serde_json::to_string(&Patch { ttl: Some(None) }).unwrap(),
+            r#"{"ttl":null}"#
+        );
+    }
+}
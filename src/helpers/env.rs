@@ -49,8 +49,8 @@ mod tests {
 
     #[test]
     fn test_get_float_from_env_valid() {
-        env::set_var("TEST_FLOAT", "3.14");
-        assert_eq!(get_float("TEST_FLOAT"), 3.14);
+        env::set_var("TEST_FLOAT", "2.5");
+        assert_eq!(get_float("TEST_FLOAT"), 2.5);
         env::remove_var("TEST_FLOAT");
     }
 
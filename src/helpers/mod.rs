@@ -1,4 +1,8 @@
+pub mod base64_bytes;
+pub mod cipher;
 pub mod datetime;
+pub mod double_option;
 pub mod email;
 pub mod env;
+pub mod rate_limit;
 pub mod uuid;
@@ -0,0 +1,292 @@
+use std::time::{Duration, Instant};
+
+/**
+ * `TokenBucket` - Holds up to `capacity` tokens and gets `rate` of them back per second
+ */
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    capacity: u64,
+    rate: u64,
+    tokens: u64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate: u32) -> Self {
+        Self {
+            capacity: u64::from(rate),
+            rate: u64::from(rate),
+            tokens: u64::from(rate),
+            last_refill: Instant::now(),
+        }
+    }
+
+    /**
+     * Takes the tokens if there are enough left - nothing is taken otherwise
+     */
+    pub fn try_take(&mut self, amount: u64, now: Instant) -> bool {
+        self.refill(now);
+
+        if amount > self.tokens {
+            return false;
+        }
+
+        self.tokens -= amount;
+
+        true
+    }
+
+    /**
+     * Whether all tokens are back - a full bucket is the same as a new one
+     */
+    pub fn is_full(&self, now: Instant) -> bool {
+        self.tokens.saturating_add(self.refill_since(now)) >= self.capacity
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let refill = self.refill_since(now);
+
+        // Fractions of a token stay with the clock until they add up
+        if refill > 0 {
+            self.tokens = self.tokens.saturating_add(refill).min(self.capacity);
+            self.last_refill = now;
+        }
+    }
+
+    fn refill_since(&self, now: Instant) -> u64 {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+
+        u64::try_from(elapsed.as_micros() * u128::from(self.rate) / 1_000_000).unwrap_or(u64::MAX)
+    }
+}
+
+/**
+ * `Verdict` - What to do with a frame after checking it against a `RateLimit`
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Allowed,
+    /// Over the limit - the frame gets rejected
+    Limited,
+    /// Over the limit once too often - the session gets closed
+    Banned,
+}
+
+/**
+ * `Budget` - Which frames per second a frame is taken from
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    Messages,
+    /// Receipts and pings - they come in bursts, e.g. for everything replayed on reconnect
+    Receipts,
+}
+
+/**
+ * `RateLimit` - Frames (messages and receipts on their own) and bytes per second, allowing bursts of one second worth
+ * of them. Every rejected frame is a strike, whoever collected `max_strikes` of them within `strike_window` is banned
+ * until that window passed.
+ */
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    messages: TokenBucket,
+    receipts: TokenBucket,
+    bytes: TokenBucket,
+    strikes: u32,
+    max_strikes: u32,
+    strike_window: Duration,
+    /// Start of the current strike window - `None` while there are no strikes
+    first_strike: Option<Instant>,
+}
+
+impl RateLimit {
+    pub fn new(
+        messages_per_second: u32,
+        receipts_per_second: u32,
+        bytes_per_second: u32,
+        max_strikes: u32,
+        strike_window: Duration,
+    ) -> Self {
+        Self {
+            messages: TokenBucket::new(messages_per_second),
+            receipts: TokenBucket::new(receipts_per_second),
+            bytes: TokenBucket::new(bytes_per_second),
+            strikes: 0,
+            max_strikes,
+            strike_window,
+            first_strike: None,
+        }
+    }
+
+    pub fn check(&mut self, size: usize, budget: Budget, now: Instant) -> Verdict {
+        // Banned ones stay so until the strike window passed, whatever budget is left
+        if self.is_banned(now) {
+            return Verdict::Banned;
+        }
+
+        // Only frames within both limits count against them
+        let mut bytes = self.bytes.clone();
+        let frames = match budget {
+            Budget::Messages => &mut self.messages,
+            Budget::Receipts => &mut self.receipts,
+        };
+
+        if bytes.try_take(u64::try_from(size).unwrap_or(u64::MAX), now) && frames.try_take(1, now) {
+            self.bytes = bytes;
+
+            return Verdict::Allowed;
+        }
+
+        // Strikes are forgiven once the window since the first of them passed
+        if !self.within_strike_window(now) {
+            self.strikes = 0;
+            self.first_strike = Some(now);
+        }
+
+        self.strikes += 1;
+
+        if self.strikes >= self.max_strikes {
+            Verdict::Banned
+        } else {
+            Verdict::Limited
+        }
+    }
+
+    pub fn is_banned(&self, now: Instant) -> bool {
+        self.strikes >= self.max_strikes && self.within_strike_window(now)
+    }
+
+    /**
+     * Whether there is nothing left to remember: all budgets are full again and the strikes are forgiven
+     */
+    pub fn is_settled(&self, now: Instant) -> bool {
+        !self.within_strike_window(now)
+            && self.messages.is_full(now)
+            && self.receipts.is_full(now)
+            && self.bytes.is_full(now)
+    }
+
+    fn within_strike_window(&self, now: Instant) -> bool {
+        self.first_strike
+            .is_some_and(|first_strike| now.saturating_duration_since(first_strike) < self.strike_window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Budget, RateLimit, TokenBucket, Verdict};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_token_bucket() {
+        let mut bucket = TokenBucket::new(10);
+        let start = Instant::now();
+
+        assert!(bucket.try_take(10, start));
+        assert!(!bucket.try_take(1, start));
+        assert!(!bucket.try_take(1, start + Duration::from_millis(50)));
+        assert!(bucket.try_take(1, start + Duration::from_millis(100)));
+        assert!(bucket.try_take(5, start + Duration::from_millis(600)));
+        assert!(!bucket.try_take(11, start + Duration::from_secs(60)));
+        assert!(bucket.try_take(10, start + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_rate_limit_messages() {
+        let mut rate_limit = RateLimit::new(2, 2, 1000, 3, Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert_eq!(rate_limit.check(10, Budget::Messages, start), Verdict::Allowed);
+        assert_eq!(rate_limit.check(10, Budget::Messages, start), Verdict::Allowed);
+        assert_eq!(rate_limit.check(10, Budget::Messages, start), Verdict::Limited);
+        assert_eq!(
+            rate_limit.check(10, Budget::Messages, start + Duration::from_millis(500)),
+            Verdict::Allowed
+        );
+        assert_eq!(
+            rate_limit.check(10, Budget::Messages, start + Duration::from_millis(500)),
+            Verdict::Limited
+        );
+        assert_eq!(
+            rate_limit.check(10, Budget::Messages, start + Duration::from_millis(500)),
+            Verdict::Banned
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_bytes() {
+        let mut rate_limit = RateLimit::new(10, 10, 100, 5, Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert_eq!(rate_limit.check(80, Budget::Messages, start), Verdict::Allowed);
+        assert_eq!(rate_limit.check(30, Budget::Messages, start), Verdict::Limited);
+        // Rejected frames don't use up any of the limits
+        assert_eq!(rate_limit.check(20, Budget::Messages, start), Verdict::Allowed);
+        assert_eq!(
+            rate_limit.check(101, Budget::Messages, start + Duration::from_secs(5)),
+            Verdict::Limited
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_receipts() {
+        let mut rate_limit = RateLimit::new(1, 3, 1000, 2, Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert_eq!(rate_limit.check(10, Budget::Messages, start), Verdict::Allowed);
+        // Receipts have a budget of their own
+        assert_eq!(rate_limit.check(10, Budget::Receipts, start), Verdict::Allowed);
+        assert_eq!(rate_limit.check(10, Budget::Receipts, start), Verdict::Allowed);
+        assert_eq!(rate_limit.check(10, Budget::Receipts, start), Verdict::Allowed);
+        assert_eq!(rate_limit.check(10, Budget::Receipts, start), Verdict::Limited);
+        assert_eq!(rate_limit.check(10, Budget::Messages, start), Verdict::Banned);
+    }
+
+    #[test]
+    fn test_rate_limit_strikes_expire() {
+        let mut rate_limit = RateLimit::new(1, 1, 1000, 2, Duration::from_secs(60));
+        let start = Instant::now();
+        let later = start + Duration::from_secs(61);
+
+        assert_eq!(rate_limit.check(10, Budget::Messages, start), Verdict::Allowed);
+        assert_eq!(rate_limit.check(10, Budget::Messages, start), Verdict::Limited);
+        // The strike of the window before is forgiven
+        assert_eq!(rate_limit.check(10, Budget::Messages, later), Verdict::Allowed);
+        assert_eq!(rate_limit.check(10, Budget::Messages, later), Verdict::Limited);
+        assert_eq!(rate_limit.check(10, Budget::Messages, later), Verdict::Banned);
+    }
+
+    #[test]
+    fn test_token_bucket_full() {
+        let mut bucket = TokenBucket::new(10);
+        let start = Instant::now();
+
+        assert!(bucket.is_full(start));
+        assert!(bucket.try_take(5, start));
+        assert!(!bucket.is_full(start + Duration::from_millis(400)));
+        assert!(bucket.is_full(start + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_rate_limit_ban_and_settle() {
+        let mut rate_limit = RateLimit::new(1, 1, 1000, 2, Duration::from_secs(60));
+        let start = Instant::now();
+        let later = start + Duration::from_secs(61);
+
+        assert!(rate_limit.is_settled(start));
+        assert_eq!(rate_limit.check(10, Budget::Messages, start), Verdict::Allowed);
+        assert!(!rate_limit.is_settled(start));
+        assert_eq!(rate_limit.check(10, Budget::Messages, start), Verdict::Limited);
+        assert_eq!(rate_limit.check(10, Budget::Messages, start), Verdict::Banned);
+        assert!(rate_limit.is_banned(start));
+
+        // Refilled budgets don't lift the ban, only the strike window passing does
+        let refilled = start + Duration::from_secs(5);
+        assert_eq!(rate_limit.check(10, Budget::Receipts, refilled), Verdict::Banned);
+        assert!(!rate_limit.is_settled(refilled));
+
+        assert!(!rate_limit.is_banned(later));
+        assert!(rate_limit.is_settled(later));
+        assert_eq!(rate_limit.check(10, Budget::Messages, later), Verdict::Allowed);
+    }
+}
@@ -4,7 +4,14 @@ use actix_web::{HttpRequest, HttpResponse};
 use uuid::Uuid;
 
 pub fn parse_uuid_from_request(request: &HttpRequest) -> Result<Uuid, HttpResponse> {
-    let uuid_option = request.match_info().get("uuid");
+    parse_named_uuid_from_request(request, "uuid")
+}
+
+/**
+ * Same as `parse_uuid_from_request` for routes with more than one uuid in their path
+ */
+pub fn parse_named_uuid_from_request(request: &HttpRequest, name: &str) -> Result<Uuid, HttpResponse> {
+    let uuid_option = request.match_info().get(name);
     let uuid_string: &str;
 
     match uuid_option {
@@ -34,7 +41,7 @@ pub fn parse_uuid_from_request(request: &HttpRequest) -> Result<Uuid, HttpRespon
 
 #[cfg(test)]
 mod tests {
-    use super::parse_uuid_from_request;
+    use super::{parse_named_uuid_from_request, parse_uuid_from_request};
     use actix_web::body::to_bytes;
     use actix_web::web::{get, scope, ServiceConfig};
     use actix_web::{test, App, HttpResponse};
@@ -42,7 +49,11 @@ mod tests {
     use uuid::Uuid;
 
     fn test_factory(app: &mut ServiceConfig) {
-        app.service(scope("v1/test").route("get/{uuid}", get().to(test_handler)));
+        app.service(
+            scope("v1/test")
+                .route("get/{uuid}", get().to(test_handler))
+                .route("get/{uuid}/member/{user_uuid}", get().to(test_handler)),
+        );
     }
     async fn test_handler() -> HttpResponse {
         HttpResponse::Ok().json("test")
@@ -51,11 +62,11 @@ mod tests {
     #[actix_rt::test]
     async fn test_parse_uuid_from_request_valid() {
         let uuid = Uuid::new_v4().to_string();
-        let mut app = test::init_service(App::new().configure(test_factory)).await;
+        let app = test::init_service(App::new().configure(test_factory)).await;
         let request = test::TestRequest::with_uri(&format!("/v1/test/get/{}", uuid)).to_request();
 
         // We have to create the response in order to get the correct request needed
-        let response = test::call_service(&mut app, request).await;
+        let response = test::call_service(&app, request).await;
 
         assert_eq!(
             parse_uuid_from_request(&response.request().clone())
@@ -67,11 +78,11 @@ mod tests {
 
     #[actix_rt::test]
     async fn test_parse_uuid_from_request_invalid() {
-        let mut app = test::init_service(App::new().configure(test_factory)).await;
+        let app = test::init_service(App::new().configure(test_factory)).await;
         let request = test::TestRequest::with_uri("/v1/test/get/no-uuid").to_request();
 
         // We have to create the response in order to get the correct request needed
-        let response = test::call_service(&mut app, request).await;
+        let response = test::call_service(&app, request).await;
 
         match parse_uuid_from_request(&response.request().clone()) {
             Ok(_) => panic!("Test for parsing uuid from request fails!"),
@@ -87,11 +98,11 @@ mod tests {
 
     #[actix_rt::test]
     async fn test_parse_uuid_from_request_missing() {
-        let mut app = test::init_service(App::new().configure(test_factory)).await;
+        let app = test::init_service(App::new().configure(test_factory)).await;
         let request = test::TestRequest::with_uri("/v1/test/get/").to_request();
 
         // We have to create the response in order to get the correct request needed
-        let response = test::call_service(&mut app, request).await;
+        let response = test::call_service(&app, request).await;
 
         match parse_uuid_from_request(&response.request().clone()) {
             Ok(_) => panic!("Test for parsing uuid from request fails!"),
@@ -104,4 +115,28 @@ mod tests {
             }
         }
     }
+
+    #[actix_rt::test]
+    async fn test_parse_named_uuid_from_request_valid() {
+        let uuid = Uuid::new_v4().to_string();
+        let user_uuid = Uuid::new_v4().to_string();
+        let app = test::init_service(App::new().configure(test_factory)).await;
+        let request = test::TestRequest::with_uri(&format!("/v1/test/get/{}/member/{}", uuid, user_uuid)).to_request();
+
+        // We have to create the response in order to get the correct request needed
+        let response = test::call_service(&app, request).await;
+
+        assert_eq!(
+            parse_named_uuid_from_request(&response.request().clone(), "user_uuid")
+                .unwrap()
+                .to_string(),
+            user_uuid
+        );
+        assert_eq!(
+            parse_uuid_from_request(&response.request().clone())
+                .unwrap()
+                .to_string(),
+            uuid
+        );
+    }
 }
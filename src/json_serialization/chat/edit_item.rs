@@ -0,0 +1,59 @@
+use crate::models::chat::retention::Retention;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+#[allow(clippy::option_option)]
+pub struct EditItem {
+    pub name: String,
+    pub allow_anonymous: bool,
+    /// Seconds messages live for by default - `null` keeps them forever, leaving it out keeps the current one
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::helpers::double_option"
+    )]
+    pub default_ttl: Option<Option<i32>>,
+    /// Leaving it out keeps the current retention
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention: Option<Retention>,
+}
+
+#[cfg(test)]
+mod edit_chat_item_tests {
+    use super::EditItem;
+    use crate::models::chat::retention::Retention;
+
+    #[test]
+    fn serialize() {
+        let edit_item = EditItem {
+            name: "my chat".to_string(),
+            allow_anonymous: false,
+            default_ttl: Some(Some(3600)),
+            retention: Some(Retention::Days(30)),
+        };
+
+        let serialized = serde_json::to_string(&edit_item).unwrap();
+        let expected = r#"{"name":"my chat","allow_anonymous":false,"default_ttl":3600,"retention":{"Days":30}}"#;
+
+        assert_eq!(serialized, expected);
+    }
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"name":"my chat","allow_anonymous":false}"#;
+        let deserialized: EditItem = serde_json::from_str(json).unwrap();
+
+        assert_eq!(deserialized.name, "my chat");
+        assert!(!deserialized.allow_anonymous);
+        assert_eq!(deserialized.default_ttl, None);
+        assert_eq!(deserialized.retention, None);
+    }
+
+    #[test]
+    fn deserialize_clearing_default_ttl() {
+        let json = r#"{"name":"my chat","allow_anonymous":false,"default_ttl":null}"#;
+        let deserialized: EditItem = serde_json::from_str(json).unwrap();
+
+        assert_eq!(deserialized.default_ttl, Some(None));
+    }
+}
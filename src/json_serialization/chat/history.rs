@@ -0,0 +1,110 @@
+use crate::json_serialization::web_socket::chat_message::ChatMessage;
+use crate::models::chat_message::item::ChatMessage as ChatMessageModel;
+use serde::Serialize;
+
+/**
+ * `History` - One page of the (still encrypted) message history, shaped like the `ChatMessage` of the WebSocket
+ * with the reactions counted per emoji
+ */
+#[derive(Serialize)]
+pub struct History {
+    pub chat_messages: Vec<ChatMessage>,
+    pub chat_messages_count: usize,
+    pub has_more: bool,
+}
+
+impl History {
+    pub fn new(input_items: Vec<ChatMessageModel>, reactions: &[(i32, String, i64)], has_more: bool) -> Self {
+        let mut message_array_buffer = Vec::new();
+
+        for item in input_items {
+            let id = item.id;
+            let mut chat_message = ChatMessage::from_model(item);
+
+            for (_, emoji, count) in reactions
+                .iter()
+                .filter(|(chat_message_id, _, _)| *chat_message_id == id)
+            {
+                chat_message.reactions.insert(emoji.clone(), *count);
+            }
+
+            message_array_buffer.push(chat_message);
+        }
+
+        let open_count = message_array_buffer.len();
+
+        Self {
+            chat_messages: message_array_buffer,
+            chat_messages_count: open_count,
+            has_more,
+        }
+    }
+}
+
+#[cfg(test)]
+mod chat_history_tests {
+    use super::History;
+    use crate::models::chat_message::item::ChatMessage;
+    use chrono::NaiveDateTime;
+    use uuid::Uuid;
+
+    #[test]
+    fn serialize() {
+        let time = NaiveDateTime::parse_from_str("2023-10-01T12:34:56", "%Y-%m-%dT%H:%M:%S").unwrap();
+        let message = ChatMessage {
+            id: 1,
+            chat_id: 1,
+            creator_id: None,
+            uuid: Uuid::parse_str("72655de0-21e6-40f0-9856-9530344bf78d").unwrap(),
+            creation_date: time,
+            cipher: "ciphertext".to_string(),
+            iv: "iv123".to_string(),
+            sender_uuid: Uuid::parse_str("85979ec6-66c5-4ba4-9153-606f2e9e2f6a").unwrap(),
+            client_id: None,
+            expiration_date: None,
+            burn_after_read: false,
+            modification_date: None,
+            deletion_date: None,
+            reply_to: None,
+        };
+
+        let messages = History::new(vec![message], &[(2, "🎉".to_string(), 1)], true);
+        assert_eq!(messages.chat_messages_count, 1);
+
+        let serialized = serde_json::to_string(&messages).unwrap();
+        let expected = r#"{"chat_messages":[{"uuid":"72655de0-21e6-40f0-9856-9530344bf78d","user_id":"85979ec6-66c5-4ba4-9153-606f2e9e2f6a","cipher":"ciphertext","iv":"iv123","message_sent_at":"2023-10-01T12:34:56"}],"chat_messages_count":1,"has_more":true}"#;
+
+        assert_eq!(serialized, expected);
+    }
+
+    #[test]
+    fn serialize_reactions() {
+        let time = NaiveDateTime::parse_from_str("2023-10-01T12:34:56", "%Y-%m-%dT%H:%M:%S").unwrap();
+        let message = ChatMessage {
+            id: 1,
+            chat_id: 1,
+            creator_id: None,
+            uuid: Uuid::parse_str("72655de0-21e6-40f0-9856-9530344bf78d").unwrap(),
+            creation_date: time,
+            cipher: "ciphertext".to_string(),
+            iv: "iv123".to_string(),
+            sender_uuid: Uuid::parse_str("85979ec6-66c5-4ba4-9153-606f2e9e2f6a").unwrap(),
+            client_id: None,
+            expiration_date: None,
+            burn_after_read: false,
+            modification_date: None,
+            deletion_date: None,
+            reply_to: None,
+        };
+        let reactions = vec![
+            (1, "👍".to_string(), 2),
+            (1, "🎉".to_string(), 1),
+            (2, "👍".to_string(), 5),
+        ];
+
+        let messages = History::new(vec![message], &reactions, false);
+        let serialized = serde_json::to_string(&messages.chat_messages[0]).unwrap();
+
+        assert!(serialized.ends_with(r#""reactions":{"🎉":1,"👍":2}}"#));
+    }
+}
@@ -0,0 +1,88 @@
+use crate::helpers::datetime::format;
+use crate::models::chat::item::Chat;
+use crate::models::chat::retention::Retention;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct Item {
+    pub uuid: String,
+    pub name: String,
+    pub allow_anonymous: bool,
+    pub default_ttl: Option<i32>,
+    pub retention: Retention,
+    pub creation_date: String,
+    pub modification_date: Option<String>,
+    pub deletion_date: Option<String>,
+}
+
+impl Item {
+    pub fn new(input_item: &Chat) -> Self {
+        Self {
+            uuid: input_item.uuid.to_string(),
+            name: input_item.name.clone(),
+            allow_anonymous: input_item.allow_anonymous,
+            default_ttl: input_item.default_ttl,
+            retention: input_item.retention(),
+            creation_date: input_item.creation_date.to_string(),
+            modification_date: format(input_item.modification_date),
+            deletion_date: format(input_item.deletion_date),
+        }
+    }
+}
+
+#[cfg(test)]
+mod chat_item_tests {
+    use super::Item;
+    use crate::helpers::datetime::format;
+    use crate::models::chat::item::Chat;
+    use chrono::{NaiveDateTime, Utc};
+    use uuid::Uuid;
+
+    fn create_test_chat(uuid: Uuid, time: NaiveDateTime) -> Chat {
+        Chat {
+            id: 0,
+            creator_id: 1,
+            uuid,
+            name: "test chat".to_string(),
+            share_uri: None,
+            creation_date: time,
+            modification_date: Some(time),
+            deletion_date: None,
+            allow_anonymous: true,
+            share_uri_expiration_date: None,
+            share_uri_max_uses: None,
+            share_uri_uses: 0,
+            default_ttl: None,
+            retention_policy: "Forever".to_string(),
+            retention_days: None,
+        }
+    }
+
+    #[test]
+    fn new() {
+        let time = Utc::now().naive_utc();
+        let uuid = Uuid::new_v4();
+        let test_chat = create_test_chat(uuid, time);
+        let chat_item = Item::new(&test_chat);
+
+        assert_eq!(chat_item.uuid, test_chat.uuid.to_string());
+        assert_eq!(chat_item.name, test_chat.name);
+        assert!(chat_item.allow_anonymous);
+        assert_eq!(chat_item.creation_date, time.to_string());
+        assert_eq!(chat_item.modification_date, format(test_chat.modification_date));
+        assert_eq!(chat_item.deletion_date, format(test_chat.deletion_date));
+    }
+
+    #[test]
+    fn serialize() {
+        let time = NaiveDateTime::parse_from_str("2022-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let uuid = Uuid::parse_str("6023454a-2dd5-495f-86ba-9523cf645396").unwrap();
+        let test_chat = create_test_chat(uuid, time);
+        let chat_item = Item::new(&test_chat);
+
+        let serialized = serde_json::to_string(&chat_item).unwrap();
+        let expected = r#"{"uuid":"6023454a-2dd5-495f-86ba-9523cf645396","name":"test chat","allow_anonymous":true,"default_ttl":null,"retention":"Forever","creation_date":"2022-01-01 00:00:00","modification_date":"2022-01-01 00:00:00","deletion_date":null}"#;
+
+        assert_eq!(serialized, expected);
+    }
+}
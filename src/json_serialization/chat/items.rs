@@ -0,0 +1,81 @@
+use crate::json_serialization::chat::item::Item;
+use crate::models::chat::item::Chat;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct Items {
+    pub chat_items: Vec<Item>,
+    pub chat_items_count: usize,
+}
+
+impl Items {
+    pub fn new(input_items: Vec<Chat>) -> Self {
+        let mut chat_array_buffer = Vec::new();
+
+        for item in input_items {
+            let chat_item = Item::new(&item);
+
+            chat_array_buffer.push(chat_item);
+        }
+
+        let open_count = chat_array_buffer.len();
+
+        Self {
+            chat_items: chat_array_buffer,
+            chat_items_count: open_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod chat_items_tests {
+    use super::Items;
+    use crate::models::chat::item::Chat;
+    use chrono::NaiveDateTime;
+    use uuid::Uuid;
+
+    fn create_sample_chat(uuid: Uuid, name: &str, time: NaiveDateTime) -> Chat {
+        Chat {
+            id: 0,
+            creator_id: 1,
+            uuid,
+            name: name.to_string(),
+            share_uri: None,
+            creation_date: time,
+            modification_date: None,
+            deletion_date: None,
+            allow_anonymous: false,
+            share_uri_expiration_date: None,
+            share_uri_max_uses: None,
+            share_uri_uses: 0,
+            default_ttl: None,
+            retention_policy: "Forever".to_string(),
+            retention_days: None,
+        }
+    }
+
+    #[test]
+    fn serialize() {
+        let time = NaiveDateTime::parse_from_str("2022-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let chats = vec![
+            create_sample_chat(
+                Uuid::parse_str("72655de0-21e6-40f0-9856-9530344bf78d").unwrap(),
+                "chat1",
+                time,
+            ),
+            create_sample_chat(
+                Uuid::parse_str("85979ec6-66c5-4ba4-9153-606f2e9e2f6a").unwrap(),
+                "chat2",
+                time,
+            ),
+        ];
+
+        let chat_items = Items::new(chats);
+        assert_eq!(chat_items.chat_items_count, 2);
+
+        let serialized = serde_json::to_string(&chat_items).unwrap();
+        let expected = r#"{"chat_items":[{"uuid":"72655de0-21e6-40f0-9856-9530344bf78d","name":"chat1","allow_anonymous":false,"default_ttl":null,"retention":"Forever","creation_date":"2022-01-01 00:00:00","modification_date":null,"deletion_date":null},{"uuid":"85979ec6-66c5-4ba4-9153-606f2e9e2f6a","name":"chat2","allow_anonymous":false,"default_ttl":null,"retention":"Forever","creation_date":"2022-01-01 00:00:00","modification_date":null,"deletion_date":null}],"chat_items_count":2}"#;
+
+        assert_eq!(serialized, expected);
+    }
+}
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+/**
+ * Query parameters for paging through the message history of a chat.
+ * `before` and `after` are message uuids used as cursors.
+ */
+#[derive(Deserialize)]
+pub struct MessageQuery {
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub limit: Option<i64>,
+}
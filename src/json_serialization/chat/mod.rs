@@ -0,0 +1,6 @@
+pub mod edit_item;
+pub mod history;
+pub mod item;
+pub mod items;
+pub mod message_query;
+pub mod new_item;
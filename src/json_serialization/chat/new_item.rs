@@ -0,0 +1,42 @@
+use crate::models::chat::retention::Retention;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct NewItem {
+    pub name: String,
+    #[serde(default)]
+    pub allow_anonymous: bool,
+    /// Seconds messages live for by default - forever without
+    #[serde(default)]
+    pub default_ttl: Option<i32>,
+    /// Messages are kept forever without
+    #[serde(default)]
+    pub retention: Retention,
+}
+
+#[cfg(test)]
+mod new_chat_item_tests {
+    use super::NewItem;
+    use crate::models::chat::retention::Retention;
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"name":"my chat","allow_anonymous":true,"default_ttl":60,"retention":"DeleteOnEmpty"}"#;
+        let deserialized: NewItem = serde_json::from_str(json).unwrap();
+
+        assert_eq!(deserialized.name, "my chat");
+        assert!(deserialized.allow_anonymous);
+        assert_eq!(deserialized.default_ttl, Some(60));
+        assert_eq!(deserialized.retention, Retention::DeleteOnEmpty);
+    }
+
+    #[test]
+    fn deserialize_defaults_to_authenticated_only() {
+        let json = r#"{"name":"my chat"}"#;
+        let deserialized: NewItem = serde_json::from_str(json).unwrap();
+
+        assert!(!deserialized.allow_anonymous);
+        assert_eq!(deserialized.default_ttl, None);
+        assert_eq!(deserialized.retention, Retention::Forever);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::helpers::datetime::format;
+use crate::models::chat_attachment::item::ChatAttachment;
+use serde::{Deserialize, Serialize};
+
+/**
+ * `Item` - Metadata of an uploaded attachment, to be announced in the chat with a `Data::Attachment`
+ */
+#[derive(Deserialize, Serialize)]
+pub struct Item {
+    pub uuid: String,
+    pub uploader_uuid: String,
+    pub size: i64,
+    pub content_hash: String,
+    pub creation_date: String,
+    pub expiration_date: Option<String>,
+}
+
+impl Item {
+    pub fn new(input_item: &ChatAttachment) -> Self {
+        Self {
+            uuid: input_item.uuid.to_string(),
+            uploader_uuid: input_item.uploader_uuid.to_string(),
+            size: input_item.size,
+            content_hash: input_item.content_hash.clone(),
+            creation_date: input_item.creation_date.to_string(),
+            expiration_date: format(input_item.expiration_date),
+        }
+    }
+}
+
+#[cfg(test)]
+mod chat_attachment_item_tests {
+    use super::Item;
+    use crate::models::chat_attachment::item::ChatAttachment;
+    use chrono::NaiveDateTime;
+    use uuid::Uuid;
+
+    #[test]
+    fn serialize() {
+        let time = NaiveDateTime::parse_from_str("2022-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let attachment = ChatAttachment {
+            id: 0,
+            chat_id: 1,
+            uuid: Uuid::parse_str("6023454a-2dd5-495f-86ba-9523cf645396").unwrap(),
+            uploader_uuid: Uuid::parse_str("85979ec6-66c5-4ba4-9153-606f2e9e2f6a").unwrap(),
+            size: 10,
+            content_hash: "2b".to_string(),
+            creation_date: time,
+            expiration_date: None,
+        };
+
+        let serialized = serde_json::to_string(&Item::new(&attachment)).unwrap();
+        let expected = r#"{"uuid":"6023454a-2dd5-495f-86ba-9523cf645396","uploader_uuid":"85979ec6-66c5-4ba4-9153-606f2e9e2f6a","size":10,"content_hash":"2b","creation_date":"2022-01-01 00:00:00","expiration_date":null}"#;
+
+        assert_eq!(serialized, expected);
+    }
+}
@@ -0,0 +1,7 @@
+use crate::models::chat_member::role::Role;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct EditItem {
+    pub role: Role,
+}
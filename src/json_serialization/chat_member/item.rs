@@ -0,0 +1,65 @@
+use crate::helpers::datetime::format;
+use crate::models::chat_member::item::ChatMember;
+use crate::models::chat_member::role::Role;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct Item {
+    pub user_uuid: String,
+    pub role: Role,
+    pub creation_date: String,
+    pub modification_date: Option<String>,
+    pub last_read_message_uuid: Option<String>,
+    pub last_read_date: Option<String>,
+    pub last_delivered_message_uuid: Option<String>,
+    pub last_delivered_date: Option<String>,
+}
+
+impl Item {
+    pub fn new(input_item: &ChatMember) -> Self {
+        Self {
+            user_uuid: input_item.user_uuid.to_string(),
+            role: input_item.role(),
+            creation_date: input_item.creation_date.to_string(),
+            modification_date: format(input_item.modification_date),
+            last_read_message_uuid: input_item.last_read_message_uuid.map(|uuid| uuid.to_string()),
+            last_read_date: format(input_item.last_read_date),
+            last_delivered_message_uuid: input_item.last_delivered_message_uuid.map(|uuid| uuid.to_string()),
+            last_delivered_date: format(input_item.last_delivered_date),
+        }
+    }
+}
+
+#[cfg(test)]
+mod chat_member_item_tests {
+    use super::Item;
+    use crate::models::chat_member::item::ChatMember;
+    use crate::models::chat_member::role::Role;
+    use chrono::NaiveDateTime;
+    use uuid::Uuid;
+
+    #[test]
+    fn serialize() {
+        let time = NaiveDateTime::parse_from_str("2022-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let member = ChatMember {
+            id: 0,
+            chat_id: 1,
+            user_uuid: Uuid::parse_str("6023454a-2dd5-495f-86ba-9523cf645396").unwrap(),
+            role: "ReadOnly".to_string(),
+            creation_date: time,
+            modification_date: None,
+            last_read_message_uuid: None,
+            last_read_date: None,
+            last_delivered_message_uuid: None,
+            last_delivered_date: None,
+        };
+        let member_item = Item::new(&member);
+
+        assert_eq!(member_item.role, Role::ReadOnly);
+
+        let serialized = serde_json::to_string(&member_item).unwrap();
+        let expected = r#"{"user_uuid":"6023454a-2dd5-495f-86ba-9523cf645396","role":"ReadOnly","creation_date":"2022-01-01 00:00:00","modification_date":null,"last_read_message_uuid":null,"last_read_date":null,"last_delivered_message_uuid":null,"last_delivered_date":null}"#;
+
+        assert_eq!(serialized, expected);
+    }
+}
@@ -0,0 +1,28 @@
+use crate::json_serialization::chat_member::item::Item;
+use crate::models::chat_member::item::ChatMember;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct Items {
+    pub member_items: Vec<Item>,
+    pub member_items_count: usize,
+}
+
+impl Items {
+    pub fn new(input_items: Vec<ChatMember>) -> Self {
+        let mut member_array_buffer = Vec::new();
+
+        for item in input_items {
+            let member_item = Item::new(&item);
+
+            member_array_buffer.push(member_item);
+        }
+
+        let open_count = member_array_buffer.len();
+
+        Self {
+            member_items: member_array_buffer,
+            member_items_count: open_count,
+        }
+    }
+}
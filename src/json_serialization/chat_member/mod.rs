@@ -0,0 +1,4 @@
+pub mod edit_item;
+pub mod item;
+pub mod items;
+pub mod new_item;
@@ -0,0 +1,30 @@
+use crate::models::chat_member::role::Role;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct NewItem {
+    pub user_uuid: String,
+    pub role: Role,
+}
+
+#[cfg(test)]
+mod new_chat_member_item_tests {
+    use super::NewItem;
+    use crate::models::chat_member::role::Role;
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{"user_uuid":"6023454a-2dd5-495f-86ba-9523cf645396","role":"Admin"}"#;
+        let deserialized: NewItem = serde_json::from_str(json).unwrap();
+
+        assert_eq!(deserialized.user_uuid, "6023454a-2dd5-495f-86ba-9523cf645396");
+        assert_eq!(deserialized.role, Role::Admin);
+    }
+
+    #[test]
+    #[should_panic]
+    fn deserialize_unknown_role() {
+        serde_json::from_str::<NewItem>(r#"{"user_uuid":"6023454a-2dd5-495f-86ba-9523cf645396","role":"Guest"}"#)
+            .unwrap();
+    }
+}
@@ -1,3 +1,7 @@
+pub mod chat;
+pub mod chat_attachment;
+pub mod chat_member;
 pub mod response;
+pub mod share;
 pub mod user;
 pub mod web_socket;
@@ -0,0 +1,60 @@
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item;
+use crate::json_serialization::response::status::Status;
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/**
+ * `Error` - REST counterpart of the WebSocket `Error` frame: answered with the code's status and the usual envelope
+ */
+#[derive(Debug)]
+pub struct Error {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl Error {
+    pub const fn new(code: ErrorCode, message: String) -> Self {
+        Self { code, message }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        self.code.status_code()
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(Item::new(Status::Error, self.message.clone(), self.code))
+    }
+}
+
+#[cfg(test)]
+mod response_error_tests {
+    use super::Error;
+    use crate::json_serialization::response::error_code::ErrorCode;
+    use actix_web::body::to_bytes;
+    use actix_web::http::StatusCode;
+    use actix_web::ResponseError;
+
+    #[actix_rt::test]
+    async fn error_response() {
+        let error = Error::new(ErrorCode::NotFound, "Error during chat lookup".to_string());
+        let response = error.error_response();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let body = to_bytes(response.into_body()).await.unwrap();
+        let expected = r#"{"status":"Error","message":"Error during chat lookup","data":"NotFound"}"#;
+
+        assert_eq!(body, expected);
+    }
+}
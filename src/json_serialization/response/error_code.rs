@@ -0,0 +1,64 @@
+use actix_web::http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+/**
+ * `ErrorCode` - the error taxonomy shared by REST responses and WebSocket `Error` frames.
+ * Each code maps to the HTTP status it is answered with on REST.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    BadRequest,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict,
+    PayloadTooLarge,
+    UnprocessableEntity,
+    TooManyRequests,
+    UpgradeRequired,
+    Internal,
+}
+
+impl ErrorCode {
+    pub const fn status_code(self) -> StatusCode {
+        match self {
+            Self::BadRequest => StatusCode::BAD_REQUEST,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Forbidden => StatusCode::FORBIDDEN,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::Conflict => StatusCode::CONFLICT,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::UnprocessableEntity => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+            Self::UpgradeRequired => StatusCode::UPGRADE_REQUIRED,
+            Self::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[cfg(test)]
+mod error_code_tests {
+    use super::ErrorCode;
+    use actix_web::http::StatusCode;
+
+    #[test]
+    fn status_code() {
+        assert_eq!(ErrorCode::BadRequest.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(ErrorCode::Forbidden.status_code(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            ErrorCode::UnprocessableEntity.status_code(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(ErrorCode::TooManyRequests.status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(ErrorCode::UpgradeRequired.status_code(), StatusCode::UPGRADE_REQUIRED);
+        assert_eq!(ErrorCode::Internal.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn serialize() {
+        assert_eq!(
+            serde_json::to_string(&ErrorCode::PayloadTooLarge).unwrap(),
+            r#""PayloadTooLarge""#
+        );
+    }
+}
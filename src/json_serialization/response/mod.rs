@@ -1,2 +1,4 @@
+pub mod error;
+pub mod error_code;
 pub mod item;
 pub mod status;
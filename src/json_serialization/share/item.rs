@@ -0,0 +1,22 @@
+use crate::helpers::datetime::format;
+use crate::models::chat::item::Chat;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct Item {
+    pub chat_uuid: String,
+    pub share_token: String,
+    pub expiration_date: Option<String>,
+    pub max_uses: Option<i32>,
+}
+
+impl Item {
+    pub fn new(share_token: String, input_item: &Chat) -> Self {
+        Self {
+            chat_uuid: input_item.uuid.to_string(),
+            share_token,
+            expiration_date: format(input_item.share_uri_expiration_date),
+            max_uses: input_item.share_uri_max_uses,
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod item;
+pub mod new_item;
+pub mod redeem_item;
+pub mod redeemed;
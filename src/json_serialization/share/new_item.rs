@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/**
+ * Both limits are optional: without them the share link is valid until it gets revoked
+ */
+#[derive(Deserialize, Serialize)]
+pub struct NewItem {
+    pub max_uses: Option<i32>,
+    /// Lifetime in seconds
+    pub lifetime: Option<i64>,
+}
+
+#[cfg(test)]
+mod new_share_item_tests {
+    use super::NewItem;
+
+    #[test]
+    fn deserialize() {
+        let deserialized: NewItem = serde_json::from_str(r#"{"max_uses":1,"lifetime":3600}"#).unwrap();
+
+        assert_eq!(deserialized.max_uses, Some(1));
+        assert_eq!(deserialized.lifetime, Some(3600));
+    }
+
+    #[test]
+    fn deserialize_without_limits() {
+        let deserialized: NewItem = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(deserialized.max_uses, None);
+        assert_eq!(deserialized.lifetime, None);
+    }
+}
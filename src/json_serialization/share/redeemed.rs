@@ -0,0 +1,44 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+/**
+ * Result of redeeming a share link: the chat to connect to and the identity within it.
+ * Anonymous guests additionally receive a ticket for opening a WebSocket to (only) this chat with their freshly
+ * created identity and a guest token for renewing it - no session token, so they can't use anything but the chat
+ * they were invited to.
+ */
+#[derive(Serialize)]
+pub struct Redeemed {
+    pub chat_uuid: String,
+    pub user_uuid: String,
+    pub ticket: Option<String>,
+    pub guest_token: Option<String>,
+}
+
+impl Redeemed {
+    pub fn new(chat_uuid: Uuid, user_uuid: Uuid, ticket: Option<String>, guest_token: Option<String>) -> Self {
+        Self {
+            chat_uuid: chat_uuid.to_string(),
+            user_uuid: user_uuid.to_string(),
+            ticket,
+            guest_token,
+        }
+    }
+}
+
+#[cfg(test)]
+mod redeemed_tests {
+    use super::Redeemed;
+    use uuid::Uuid;
+
+    #[test]
+    fn serialize() {
+        let chat_uuid = Uuid::parse_str("72655de0-21e6-40f0-9856-9530344bf78d").unwrap();
+        let user_uuid = Uuid::parse_str("6023454a-2dd5-495f-86ba-9523cf645396").unwrap();
+
+        let serialized = serde_json::to_string(&Redeemed::new(chat_uuid, user_uuid, None, None)).unwrap();
+        let expected = r#"{"chat_uuid":"72655de0-21e6-40f0-9856-9530344bf78d","user_uuid":"6023454a-2dd5-495f-86ba-9523cf645396","ticket":null,"guest_token":null}"#;
+
+        assert_eq!(serialized, expected);
+    }
+}
@@ -59,7 +59,7 @@ mod user_item_tests {
     fn new() {
         let time = Utc::now().naive_utc();
         let uuid = Uuid::new_v4();
-        let test_user = create_test_user(uuid, time.clone());
+        let test_user = create_test_user(uuid, time);
         let user_item = Item::new(&test_user);
 
         assert_eq!(user_item.uuid, test_user.uuid.to_string());
@@ -79,7 +79,7 @@ mod user_item_tests {
 
         let time = NaiveDateTime::parse_from_str("2022-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
         let uuid = Uuid::parse_str("6023454a-2dd5-495f-86ba-9523cf645396").unwrap();
-        let test_user = create_test_user(uuid, time.clone());
+        let test_user = create_test_user(uuid, time);
         let user_item = Item::new(&test_user);
 
         let serialized = serde_json::to_string(&user_item).unwrap();
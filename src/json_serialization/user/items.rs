@@ -58,8 +58,8 @@ mod user_items_tests {
         let time = Utc::now().naive_utc();
 
         let users = vec![
-            create_sample_user(uuid1, "user1", "user1@example.com", time.clone()),
-            create_sample_user(uuid2, "user2", "user2@example.com", time.clone()),
+            create_sample_user(uuid1, "user1", "user1@example.com", time),
+            create_sample_user(uuid2, "user2", "user2@example.com", time),
         ];
 
         let user_items = Items::new(users.clone());
@@ -105,13 +105,13 @@ mod user_items_tests {
                 Uuid::parse_str(uuid_string1).unwrap(),
                 "user1",
                 "user1@example.com",
-                time.clone(),
+                time,
             ),
             create_sample_user(
                 Uuid::parse_str(uuid_string2).unwrap(),
                 "user2",
                 "user2@example.com",
-                time.clone(),
+                time,
             ),
         ];
 
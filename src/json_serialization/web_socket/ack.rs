@@ -0,0 +1,48 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/**
+ * `Ack` struct - Confirms a `ChatMessage` to its sender, referencing it by the sender's `client_id`.
+ * Resending a message with the same `client_id` gets the very same `Ack` again instead of a duplicate.
+ * Carries the `expiration_date` the server assigned for self-destructing messages.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Ack {
+    pub client_id: Option<String>,
+    pub uuid: String,
+    pub message_sent_at: NaiveDateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_date: Option<NaiveDateTime>,
+}
+
+impl Ack {
+    pub const fn new(client_id: Option<String>, uuid: String, message_sent_at: NaiveDateTime) -> Self {
+        Self {
+            client_id,
+            uuid,
+            message_sent_at,
+            expiration_date: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ack;
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn test_ack_serialize() {
+        let ack = Ack::new(
+            Some("client-1".to_string()),
+            "uuid123".to_string(),
+            NaiveDateTime::parse_from_str("2023-10-01T12:34:56", "%Y-%m-%dT%H:%M:%S").unwrap(),
+        );
+        let json = serde_json::to_string(&ack).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"client_id":"client-1","uuid":"uuid123","message_sent_at":"2023-10-01T12:34:56"}"#
+        );
+    }
+}
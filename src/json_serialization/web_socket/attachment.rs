@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/**
+ * `Attachment` struct - Announces an uploaded encrypted blob to the chat, referenced by the uuid of the upload.
+ * `size` and `content_hash` (hex encoded SHA-256 of the ciphertext) have to match the upload, so recipients can
+ * verify what they download. `cipher` and `iv` carry whatever the clients need to decrypt it (name, key, ...).
+ * Only the uploader may announce it, the server stamps `user_id` and relays it to everyone in the room.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub blob_uuid: String,
+    pub user_id: String,
+    pub size: i64,
+    pub content_hash: String,
+    #[serde(with = "crate::helpers::base64_bytes")]
+    pub cipher: String,
+    pub iv: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Attachment;
+
+    #[test]
+    fn test_attachment_serialize() {
+        let attachment = Attachment {
+            blob_uuid: "uuid123".to_string(),
+            user_id: "user123".to_string(),
+            size: 10,
+            content_hash: "2b".to_string(),
+            cipher: "ciphertext".to_string(),
+            iv: "iv123".to_string(),
+        };
+        let json = serde_json::to_string(&attachment).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"blob_uuid":"uuid123","user_id":"user123","size":10,"content_hash":"2b","cipher":"ciphertext","iv":"iv123"}"#
+        );
+    }
+
+    #[test]
+    fn test_attachment_deserialize() {
+        let json = r#"{"blob_uuid":"uuid123","user_id":"user123","size":10,"content_hash":"2b","cipher":"ciphertext","iv":"iv123"}"#;
+        let attachment: Attachment = serde_json::from_str(json).unwrap();
+
+        assert_eq!(attachment.blob_uuid, "uuid123".to_string());
+        assert_eq!(attachment.size, 10);
+        assert_eq!(attachment.content_hash, "2b".to_string());
+    }
+}
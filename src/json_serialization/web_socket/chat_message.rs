@@ -1,16 +1,41 @@
+use crate::models::chat_message::item::ChatMessage as ChatMessageModel;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /**
  * `ChatMessage` struct - Represents a chat message and contains the data of each message a user sends
+ * The `client_id` is the sender's idempotency key: it's only sent to the server and answered with an `Ack`.
+ * The `ttl` (in seconds, the chat's default applies without) is only sent to the server as well, everyone gets the
+ * resulting `expiration_date` instead. `burn_after_read` messages are deleted once every other member read them.
+ * Stored messages carry their `modification_date` once edited and `deletion_date` (without cipher) once retracted.
+ * Replies reference the `uuid` of their parent message in `reply_to` - it has to be one of the same chat.
+ * The history adds the number of `reactions` per emoji.
  */
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct ChatMessage {
     pub uuid: Option<String>,
     pub user_id: String,
+    #[serde(with = "crate::helpers::base64_bytes")]
     pub cipher: String,
     pub iv: String,
     pub message_sent_at: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_date: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub burn_after_read: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modification_date: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deletion_date: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub reactions: BTreeMap<String, i64>,
 }
 
 impl ChatMessage {
@@ -27,8 +52,36 @@ impl ChatMessage {
             cipher,
             iv,
             message_sent_at: Some(message_sent_at),
+            client_id: None,
+            ttl: None,
+            expiration_date: None,
+            burn_after_read: false,
+            modification_date: None,
+            deletion_date: None,
+            reply_to: None,
+            reactions: BTreeMap::new(),
         }
     }
+
+    /**
+     * Shapes a stored message like a relayed one
+     */
+    pub fn from_model(item: ChatMessageModel) -> Self {
+        let mut chat_message = Self::new(
+            item.uuid.to_string(),
+            item.sender_uuid.to_string(),
+            item.cipher,
+            item.iv,
+            item.creation_date,
+        );
+        chat_message.expiration_date = item.expiration_date;
+        chat_message.burn_after_read = item.burn_after_read;
+        chat_message.modification_date = item.modification_date;
+        chat_message.deletion_date = item.deletion_date;
+        chat_message.reply_to = item.reply_to.map(|uuid| uuid.to_string());
+
+        chat_message
+    }
 }
 
 #[cfg(test)]
@@ -85,4 +138,45 @@ mod tests {
             Some(NaiveDateTime::parse_from_str("2023-10-01T12:34:56", "%Y-%m-%dT%H:%M:%S").unwrap())
         );
     }
+
+    #[test]
+    fn test_chat_message_client_id() {
+        let json = r#"{"user_id":"user123","cipher":"ciphertext","iv":"iv123","client_id":"client-1"}"#;
+        let chat_message: ChatMessage = serde_json::from_str(json).unwrap();
+        assert_eq!(chat_message.client_id, Some("client-1".to_string()));
+
+        // Never relayed to others
+        let mut relayed = chat_message;
+        relayed.client_id = None;
+        assert!(!serde_json::to_string(&relayed).unwrap().contains("client_id"));
+    }
+
+    #[test]
+    fn test_chat_message_expiration() {
+        let json = r#"{"user_id":"user123","cipher":"ciphertext","iv":"iv123","ttl":60,"burn_after_read":true}"#;
+        let mut chat_message: ChatMessage = serde_json::from_str(json).unwrap();
+        assert_eq!(chat_message.ttl, Some(60));
+        assert!(chat_message.burn_after_read);
+
+        chat_message.ttl = None;
+        chat_message.expiration_date =
+            Some(NaiveDateTime::parse_from_str("2023-10-01T12:34:56", "%Y-%m-%dT%H:%M:%S").unwrap());
+        assert_eq!(
+            serde_json::to_string(&chat_message).unwrap(),
+            r#"{"uuid":null,"user_id":"user123","cipher":"ciphertext","iv":"iv123","message_sent_at":null,"expiration_date":"2023-10-01T12:34:56","burn_after_read":true}"#
+        );
+    }
+
+    #[test]
+    fn test_chat_message_reply_to() {
+        let json = r#"{"user_id":"user123","cipher":"ciphertext","iv":"iv123","reply_to":"72655de0-21e6-40f0-9856-9530344bf78d"}"#;
+        let chat_message: ChatMessage = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            chat_message.reply_to,
+            Some("72655de0-21e6-40f0-9856-9530344bf78d".to_string())
+        );
+        assert!(serde_json::to_string(&chat_message)
+            .unwrap()
+            .contains(r#""reply_to":"72655de0-21e6-40f0-9856-9530344bf78d""#));
+    }
 }
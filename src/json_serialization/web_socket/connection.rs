@@ -1,5 +1,10 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 
+/// Operations an exported public key may list - usually none at all
+const PUBLIC_KEY_OPS: [&str; 3] = ["deriveBits", "deriveKey", "verify"];
+
 /**
  * `Connection` struct - Represents the connection status of the WebSocket
  * Contains all needed user data.
@@ -33,6 +38,34 @@ pub struct PublicKey {
     pub y: String,
 }
 
+impl PublicKey {
+    /**
+     * Whether it has the shape of an exported EC public key: a known curve and both coordinates of its size
+     */
+    pub fn is_valid(&self) -> bool {
+        let coordinate_length = match self.crv.as_str() {
+            "P-256" => 32,
+            "P-384" => 48,
+            "P-521" => 66,
+            _ => return false,
+        };
+        let is_coordinate = |coordinate: &str| {
+            URL_SAFE_NO_PAD
+                .decode(coordinate)
+                .is_ok_and(|bytes| bytes.len() == coordinate_length)
+        };
+
+        self.kty == "EC"
+            && self.key_ops.len() <= PUBLIC_KEY_OPS.len()
+            && self
+                .key_ops
+                .iter()
+                .all(|key_op| PUBLIC_KEY_OPS.contains(&key_op.as_str()))
+            && is_coordinate(&self.x)
+            && is_coordinate(&self.y)
+    }
+}
+
 impl Connection {
     #[allow(dead_code)]
     pub const fn new(status: Status, user_id: String, user_name: String, public_key: PublicKey) -> Self {
@@ -160,4 +193,37 @@ mod tests {
     fn test_connection_from_string_panic_no_user_id() {
         serde_json::from_str::<Connection>(r#"{"status":"Disconnected"}"#).unwrap();
     }
+
+    #[test]
+    fn test_public_key_is_valid() {
+        let public_key = PublicKey {
+            crv: "P-384".to_string(),
+            ext: true,
+            key_ops: vec![],
+            kty: "EC".to_string(),
+            x: "Br-DF2-zNbZUrIbRcmiHw-b5QjWpOuii1KzgYQRqXvFtQrzXf410i4ir6lPBmpW0".to_string(),
+            y: "_2-ErGT-IwIg-K3TQgLkeMLfbw-CQxpmGLDGgykRxpHgfnFwENRbmkDWqPPQPHgC".to_string(),
+        };
+        assert!(public_key.is_valid());
+
+        let mut invalid = public_key.clone();
+        invalid.crv = "P-256".to_string();
+        assert!(!invalid.is_valid());
+
+        let mut invalid = public_key.clone();
+        invalid.kty = "RSA".to_string();
+        assert!(!invalid.is_valid());
+
+        let mut invalid = public_key.clone();
+        invalid.key_ops = vec!["deriveKey".to_string(), "encrypt".to_string()];
+        assert!(!invalid.is_valid());
+
+        let mut invalid = public_key.clone();
+        invalid.x.push_str("AAAA");
+        assert!(!invalid.is_valid());
+
+        let mut invalid = public_key;
+        invalid.y = "not base64!".repeat(6);
+        assert!(!invalid.is_valid());
+    }
 }
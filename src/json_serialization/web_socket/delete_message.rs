@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/**
+ * `DeleteMessage` struct - Retracts a sent `ChatMessage`: its cipher is wiped and only a tombstone is kept.
+ * Authors may retract their own messages, owners and admins any message of the chat.
+ * The server stamps `user_id` and `deletion_date` and relays it to everyone in the room.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeleteMessage {
+    pub message_uuid: String,
+    pub user_id: String,
+    pub deletion_date: Option<NaiveDateTime>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeleteMessage;
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn test_delete_message_serialize() {
+        let delete_message = DeleteMessage {
+            message_uuid: "uuid123".to_string(),
+            user_id: "user123".to_string(),
+            deletion_date: Some(NaiveDateTime::parse_from_str("2023-10-01T12:34:56", "%Y-%m-%dT%H:%M:%S").unwrap()),
+        };
+        let json = serde_json::to_string(&delete_message).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"message_uuid":"uuid123","user_id":"user123","deletion_date":"2023-10-01T12:34:56"}"#
+        );
+    }
+
+    #[test]
+    fn test_delete_message_deserialize() {
+        let json = r#"{"message_uuid":"uuid123","user_id":"user123","deletion_date":null}"#;
+        let delete_message: DeleteMessage = serde_json::from_str(json).unwrap();
+
+        assert_eq!(delete_message.message_uuid, "uuid123".to_string());
+        assert_eq!(delete_message.user_id, "user123".to_string());
+        assert_eq!(delete_message.deletion_date, None);
+    }
+}
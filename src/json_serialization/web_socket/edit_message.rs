@@ -0,0 +1,49 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/**
+ * `EditMessage` struct - Replaces the cipher (and iv) of a sent `ChatMessage`. Only its author may do so.
+ * The server stamps `user_id` and `modification_date` and relays the change to everyone in the room.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EditMessage {
+    pub message_uuid: String,
+    pub user_id: String,
+    #[serde(with = "crate::helpers::base64_bytes")]
+    pub cipher: String,
+    pub iv: String,
+    pub modification_date: Option<NaiveDateTime>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EditMessage;
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn test_edit_message_serialize() {
+        let edit_message = EditMessage {
+            message_uuid: "uuid123".to_string(),
+            user_id: "user123".to_string(),
+            cipher: "ciphertext".to_string(),
+            iv: "iv123".to_string(),
+            modification_date: Some(NaiveDateTime::parse_from_str("2023-10-01T12:34:56", "%Y-%m-%dT%H:%M:%S").unwrap()),
+        };
+        let json = serde_json::to_string(&edit_message).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"message_uuid":"uuid123","user_id":"user123","cipher":"ciphertext","iv":"iv123","modification_date":"2023-10-01T12:34:56"}"#
+        );
+    }
+
+    #[test]
+    fn test_edit_message_deserialize() {
+        let json = r#"{"message_uuid":"uuid123","user_id":"user123","cipher":"ciphertext","iv":"iv123","modification_date":null}"#;
+        let edit_message: EditMessage = serde_json::from_str(json).unwrap();
+
+        assert_eq!(edit_message.message_uuid, "uuid123".to_string());
+        assert_eq!(edit_message.cipher, "ciphertext".to_string());
+        assert_eq!(edit_message.modification_date, None);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::json_serialization::web_socket::message::{rejection, Message};
+use crate::jwt::{WS_BINARY_PROTOCOL, WS_PROTOCOL};
+
+/// Leading byte of every binary frame - bumped whenever the layout after it changes
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/**
+ * `Encoding` of the frames of one WebSocket session, negotiated via subprotocol during the handshake.
+ * `Json` sessions exchange text frames, `MessagePack` ones binary envelopes: the `ENVELOPE_VERSION` byte followed by
+ * the `Message` as `MessagePack` map (ciphertexts as raw bytes instead of base64).
+ * Incoming frames are understood in both encodings, the server answers in the negotiated one.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Json,
+    MessagePack,
+}
+
+impl Encoding {
+    /**
+     * Same choice the handshake makes: the first subprotocol requested by the client the server speaks
+     */
+    pub fn negotiate(requested_protocols: Option<&str>) -> Self {
+        requested_protocols
+            .and_then(|protocols| {
+                protocols
+                    .split(',')
+                    .map(str::trim)
+                    .find(|protocol| [WS_PROTOCOL, WS_BINARY_PROTOCOL].contains(protocol))
+            })
+            .map_or(Self::Json, |protocol| {
+                if protocol == WS_BINARY_PROTOCOL {
+                    Self::MessagePack
+                } else {
+                    Self::Json
+                }
+            })
+    }
+}
+
+pub fn encode(message: &Message) -> Vec<u8> {
+    let mut frame = vec![ENVELOPE_VERSION];
+    rmp_serde::encode::write_named(&mut frame, message).unwrap();
+
+    frame
+}
+
+/**
+ * Unpacks a binary frame - the error tells the client what's wrong with it
+ */
+pub fn decode(frame: &[u8]) -> Result<Message, String> {
+    match frame.split_first() {
+        None => Err("Empty frame".to_string()),
+        Some((&ENVELOPE_VERSION, payload)) => {
+            rmp_serde::from_slice(payload).map_err(|_| rejection(rmp_serde::from_slice(payload).ok()))
+        }
+        Some(_) => Err("Unsupported envelope version".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, Encoding, ENVELOPE_VERSION};
+    use crate::json_serialization::web_socket::chat_message::ChatMessage;
+    use crate::json_serialization::web_socket::message::{Data, Message};
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(Encoding::negotiate(None), Encoding::Json);
+        assert_eq!(Encoding::negotiate(Some("skumb")), Encoding::Json);
+        assert_eq!(Encoding::negotiate(Some("skumb.msgpack, skumb")), Encoding::MessagePack);
+        assert_eq!(Encoding::negotiate(Some("skumb, skumb.msgpack")), Encoding::Json);
+        assert_eq!(Encoding::negotiate(Some("token, skumb.msgpack")), Encoding::MessagePack);
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let message = Message::new(Data::ChatMessage(ChatMessage::new(
+            "uuid123".to_string(),
+            "user123".to_string(),
+            "Y2lwaGVydGV4dA==".to_string(),
+            "iv123".to_string(),
+            NaiveDateTime::parse_from_str("2023-10-01T12:34:56", "%Y-%m-%dT%H:%M:%S").unwrap(),
+        )));
+        let frame = encode(&message);
+
+        assert_eq!(frame[0], ENVELOPE_VERSION);
+        assert!(frame.len() < serde_json::to_vec(&message).unwrap().len());
+        assert_eq!(decode(&frame).unwrap().data, message.data);
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert_eq!(decode(&[]).unwrap_err(), "Empty frame");
+        assert_eq!(
+            decode(&[ENVELOPE_VERSION + 1, 0x80]).unwrap_err(),
+            "Unsupported envelope version"
+        );
+        assert_eq!(decode(&[ENVELOPE_VERSION, 0xc1]).unwrap_err(), "Cannot parse message");
+
+        let mut frame = vec![ENVELOPE_VERSION];
+        frame.extend(rmp_serde::to_vec_named(&serde_json::json!({"data": {"Hologram": {"depth": 3}}})).unwrap());
+
+        assert_eq!(decode(&frame).unwrap_err(), "Unsupported message type Hologram");
+    }
+}
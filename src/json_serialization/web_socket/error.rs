@@ -0,0 +1,50 @@
+use crate::json_serialization::response::error_code::ErrorCode;
+use serde::{Deserialize, Serialize};
+
+/**
+ * `Error` struct - Sent to the offending session only, instead of silently dropping what it sent.
+ * The correlation id refers to the message the error is about (if it can be told).
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub code: ErrorCode,
+    pub message: String,
+    pub correlation_id: Option<String>,
+}
+
+impl Error {
+    pub const fn new(code: ErrorCode, message: String, correlation_id: Option<String>) -> Self {
+        Self {
+            code,
+            message,
+            correlation_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use crate::json_serialization::response::error_code::ErrorCode;
+
+    #[test]
+    fn test_error_serialize() {
+        let error = Error::new(ErrorCode::Forbidden, "No write permission".to_string(), None);
+        let json = serde_json::to_string(&error).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"code":"Forbidden","message":"No write permission","correlation_id":null}"#
+        );
+    }
+
+    #[test]
+    fn test_error_deserialize() {
+        let json = r#"{"code":"BadRequest","message":"Cannot parse message","correlation_id":"abc"}"#;
+        let error: Error = serde_json::from_str(json).unwrap();
+
+        assert_eq!(error.code, ErrorCode::BadRequest);
+        assert_eq!(error.message, "Cannot parse message".to_string());
+        assert_eq!(error.correlation_id, Some("abc".to_string()));
+    }
+}
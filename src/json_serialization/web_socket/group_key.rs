@@ -13,6 +13,24 @@ pub struct GroupKey {
     pub from_user_id: String,
 }
 
+impl GroupKey {
+    pub const fn new(
+        encrypted_key: String,
+        iv: String,
+        creation_date: NaiveDateTime,
+        for_user_id: String,
+        from_user_id: String,
+    ) -> Self {
+        Self {
+            encrypted_key,
+            iv,
+            creation_date,
+            for_user_id,
+            from_user_id,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
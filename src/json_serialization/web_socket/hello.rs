@@ -0,0 +1,173 @@
+use crate::json_serialization::web_socket::message::Data;
+use serde::{Deserialize, Serialize};
+
+/// Version of the protocol spoken by this server
+pub const PROTOCOL_VERSION: u32 = 2;
+/// The protocol as it was before the handshake existed
+pub const LEGACY_PROTOCOL_VERSION: u32 = 1;
+/// The protocol version the handshake came with - and with it acks, errors and purges
+const HANDSHAKE_PROTOCOL_VERSION: u32 = 2;
+
+/**
+ * `Hello` struct - Handshake a client opens its session with: the protocol version it speaks and the optional
+ * message types it understands. The server answers with the version both sides speak (the lower one) and the
+ * capabilities it accepted - message types of other capabilities are never sent to that session.
+ * Sessions that start without a `Hello` are treated as legacy clients, which only get the core protocol as it was
+ * before the handshake.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Hello {
+    pub version: u32,
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+}
+
+/**
+ * `Capability` enum - Optional message types a client may opt into, `Unknown` ones (of newer clients) are dropped
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Capability {
+    Attachments,
+    Edits,
+    Reactions,
+    Receipts,
+    Typing,
+    #[serde(other)]
+    Unknown,
+}
+
+impl Capability {
+    /**
+     * Capability a session needs to be sent this message - `None` for the core protocol every client speaks
+     */
+    pub const fn required_for(data: &Data) -> Option<Self> {
+        match data {
+            Data::Attachment(_) => Some(Self::Attachments),
+            Data::EditMessage(_) | Data::DeleteMessage(_) => Some(Self::Edits),
+            Data::Reaction(_) => Some(Self::Reactions),
+            Data::Receipt(_) => Some(Self::Receipts),
+            Data::Typing(_) => Some(Self::Typing),
+            _ => None,
+        }
+    }
+}
+
+impl Hello {
+    pub const fn new(version: u32, capabilities: Vec<Capability>) -> Self {
+        Self { version, capabilities }
+    }
+
+    pub const fn legacy() -> Self {
+        Self::new(LEGACY_PROTOCOL_VERSION, Vec::new())
+    }
+
+    /**
+     * The answer of the server: the lower of both versions and the requested capabilities it knows
+     */
+    pub fn negotiate(&self) -> Self {
+        let mut capabilities: Vec<Capability> = self
+            .capabilities
+            .iter()
+            .copied()
+            .filter(|capability| *capability != Capability::Unknown)
+            .collect();
+        capabilities.sort_unstable();
+        capabilities.dedup();
+
+        Self::new(self.version.min(PROTOCOL_VERSION), capabilities)
+    }
+
+    pub fn understands(&self, data: &Data) -> bool {
+        self.version >= required_version(data)
+            && Capability::required_for(data).is_none_or(|capability| self.capabilities.contains(&capability))
+    }
+}
+
+/**
+ * Protocol version a session needs to be sent this core message - legacy clients don't know the newer ones
+ */
+const fn required_version(data: &Data) -> u32 {
+    match data {
+        Data::Ack(_) | Data::Error(_) | Data::Hello(_) | Data::Purged(_) => HANDSHAKE_PROTOCOL_VERSION,
+        _ => LEGACY_PROTOCOL_VERSION,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Capability, Hello, LEGACY_PROTOCOL_VERSION, PROTOCOL_VERSION};
+    use crate::json_serialization::response::error_code::ErrorCode;
+    use crate::json_serialization::web_socket::error::Error;
+    use crate::json_serialization::web_socket::message::Data;
+    use crate::json_serialization::web_socket::ping::{Knock, Ping};
+    use crate::json_serialization::web_socket::purged::Purged;
+    use crate::json_serialization::web_socket::typing::Typing;
+
+    #[test]
+    fn test_hello_serialize() {
+        let hello = Hello::new(2, vec![Capability::Reactions, Capability::Typing]);
+        let json = serde_json::to_string(&hello).unwrap();
+
+        assert_eq!(json, r#"{"version":2,"capabilities":["Reactions","Typing"]}"#);
+    }
+
+    #[test]
+    fn test_hello_deserialize() {
+        let json = r#"{"version":3,"capabilities":["Typing","Holograms"]}"#;
+        let hello: Hello = serde_json::from_str(json).unwrap();
+
+        assert_eq!(hello.capabilities, vec![Capability::Typing, Capability::Unknown]);
+
+        let hello: Hello = serde_json::from_str(r#"{"version":2}"#).unwrap();
+
+        assert!(hello.capabilities.is_empty());
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let requested = Hello::new(
+            PROTOCOL_VERSION + 1,
+            vec![
+                Capability::Typing,
+                Capability::Unknown,
+                Capability::Edits,
+                Capability::Typing,
+            ],
+        );
+        let accepted = requested.negotiate();
+
+        assert_eq!(accepted.version, PROTOCOL_VERSION);
+        assert_eq!(accepted.capabilities, vec![Capability::Edits, Capability::Typing]);
+        assert_eq!(
+            Hello::new(LEGACY_PROTOCOL_VERSION, vec![]).negotiate().version,
+            LEGACY_PROTOCOL_VERSION
+        );
+    }
+
+    #[test]
+    fn test_understands() {
+        let typing = Data::Typing(Typing {
+            user_id: "user123".to_string(),
+            is_typing: true,
+        });
+        let ping = Data::Ping(Ping::new(Knock::Pong));
+        let hello = Hello::new(PROTOCOL_VERSION, vec![Capability::Reactions]);
+
+        assert!(!hello.understands(&typing));
+        assert!(hello.understands(&ping));
+        assert!(!Hello::legacy().understands(&typing));
+        assert!(Hello::legacy().understands(&ping));
+    }
+
+    #[test]
+    fn test_understands_version() {
+        let error = Data::Error(Error::new(ErrorCode::BadRequest, "Invalid blob_uuid".to_string(), None));
+        let purged = Data::Purged(Purged::new(vec!["message_uuid".to_string()]));
+
+        // Acks, errors and purges came with the handshake, legacy clients would choke on them
+        assert!(!Hello::legacy().understands(&error));
+        assert!(!Hello::legacy().understands(&purged));
+        assert!(Hello::new(PROTOCOL_VERSION, vec![]).understands(&error));
+        assert!(Hello::new(PROTOCOL_VERSION, vec![]).understands(&purged));
+    }
+}
@@ -1,18 +1,48 @@
+use crate::json_serialization::web_socket::ack::Ack;
+use crate::json_serialization::web_socket::attachment::Attachment;
 use crate::json_serialization::web_socket::chat_message::ChatMessage;
 use crate::json_serialization::web_socket::connection::Connection;
+use crate::json_serialization::web_socket::delete_message::DeleteMessage;
+use crate::json_serialization::web_socket::edit_message::EditMessage;
+use crate::json_serialization::web_socket::error::Error;
 use crate::json_serialization::web_socket::group_key::GroupKey;
+use crate::json_serialization::web_socket::hello::Hello;
 use crate::json_serialization::web_socket::ping::Ping;
+use crate::json_serialization::web_socket::purged::Purged;
+use crate::json_serialization::web_socket::reaction::Reaction;
+use crate::json_serialization::web_socket::receipt::Receipt;
+use crate::json_serialization::web_socket::typing::Typing;
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::de::IgnoredAny;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /**
  * Message struct - Represents a message that can be sent over the WebSocket.
  * Contains a message type and the data of the message.
+ * Sent as JSON text frames, or as versioned `MessagePack` envelopes in binary frames on sessions that negotiated the
+ * `skumb.msgpack` subprotocol (see `envelope`).
  *
+ ** `Hellos` open a session with the protocol version and capabilities of the client, answered with the accepted ones.
+ ** Message types of newer protocol versions are rejected with an `Error`, the session stays open.
  ** `Pings` are used to determine if the connection is still active and the other sides still lives.
  ** `Connections` are used to change the connection status of the chat itself (active or inactive) via the participants
- ** sending their status
+ ** sending their status. The server keeps the roster: newcomers receive everyone's latest `Connection` and a
+ ** `Disconnected` one is sent on their behalf once a participant's last socket is gone
  ** `ChatMessages` are the actual messages being sent between users.
- ** `GroupKeys` are used to send the current group key to all participants but encrypted for one specific user.
+ ** Members reconnecting get the ones they didn't confirm with a `Delivered` receipt yet replayed (and their group key).
+ ** `EditMessages` replace the cipher of a sent message (authors only), `DeleteMessages` retract one and leave a
+ ** tombstone (authors, owners and admins). Both are relayed to everyone in the room, the sender included.
+ ** `Reactions` add or remove an emoji of a chat member on a message - relayed to everyone in the room as well.
+ ** `Attachments` announce an encrypted blob the sender uploaded before - relayed to everyone in the room as well.
+ ** `Acks` confirm a `ChatMessage` to its sender (only) with the uuid and timestamp the server assigned.
+ ** `GroupKeys` are used to hand the current group key to one specific participant (encrypted for them). The server
+ ** only delivers them to `for_user_id` and keeps the latest one per participant for late joiners.
+ ** `Receipts` tell the sender of a `ChatMessage` that it got delivered to or read by a recipient.
+ ** `Typings` are ephemeral indicators relayed to the other participants only - never stored and throttled per session.
+ ** `Purged` tells everyone to delete local copies of expired or burned messages - only sent by the server.
+ ** `Errors` are only sent by the server and only to the session that caused them.
+ ** Ciphers, IVs and public keys of clients are checked for size and shape before anything gets relayed or stored.
  */
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Message {
@@ -21,10 +51,53 @@ pub struct Message {
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum Data {
+    Ack(Ack),
+    Attachment(Attachment),
     ChatMessage(ChatMessage),
     Connection(Connection),
+    DeleteMessage(DeleteMessage),
+    EditMessage(EditMessage),
+    Error(Error),
     GroupKey(GroupKey),
+    Hello(Hello),
     Ping(Ping),
+    Purged(Purged),
+    Reaction(Reaction),
+    Receipt(Receipt),
+    Typing(Typing),
+}
+
+/**
+ * `Kind` enum - Only the type of a message, to tell types of newer protocol versions apart from malformed messages
+ */
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+enum Kind {
+    Ack,
+    Attachment,
+    ChatMessage,
+    Connection,
+    DeleteMessage,
+    EditMessage,
+    Error,
+    GroupKey,
+    Hello,
+    Ping,
+    Purged,
+    Reaction,
+    Receipt,
+    Typing,
+    #[serde(other)]
+    Unknown,
+}
+
+/**
+ * `Header` struct - A message with its data left unparsed
+ */
+#[derive(Deserialize, Debug)]
+pub struct Header {
+    // Only the tags are of interest
+    #[allow(clippy::zero_sized_map_values)]
+    data: HashMap<String, IgnoredAny>,
 }
 
 impl Message {
@@ -32,6 +105,29 @@ impl Message {
     pub const fn new(data: Data) -> Self {
         Self { data }
     }
+
+    /**
+     * Parses a text frame - the error tells the client what's wrong with it
+     */
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|_| rejection(serde_json::from_str(text).ok()))
+    }
+}
+
+/**
+ * Reason a frame that isn't a `Message` gets rejected with, given its `Header` (if at least that could be parsed)
+ */
+pub fn rejection(header: Option<Header>) -> String {
+    let unknown = header.and_then(|header| {
+        header.data.into_keys().find(|name| {
+            Kind::deserialize(StrDeserializer::<ValueError>::new(name)).is_ok_and(|kind| kind == Kind::Unknown)
+        })
+    });
+
+    unknown.map_or_else(
+        || "Cannot parse message".to_string(),
+        |name| format!("Unsupported message type {name}"),
+    )
 }
 
 #[cfg(test)]
@@ -45,6 +141,7 @@ mod tests {
     use crate::json_serialization::web_socket::group_key::GroupKey;
     use crate::json_serialization::web_socket::ping::Knock;
     use chrono::NaiveDateTime;
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_message_new() {
@@ -54,6 +151,14 @@ mod tests {
             cipher: "Hello encrypted cipher".to_string(),
             iv: "iv".to_string(),
             message_sent_at: Some(NaiveDateTime::parse_from_str("2023-10-01T12:34:56", "%Y-%m-%dT%H:%M:%S").unwrap()),
+            client_id: None,
+            ttl: None,
+            expiration_date: None,
+            burn_after_read: false,
+            modification_date: None,
+            deletion_date: None,
+            reply_to: None,
+            reactions: BTreeMap::new(),
         };
         let message = Message::new(Data::ChatMessage(chat.clone()));
 
@@ -68,6 +173,14 @@ mod tests {
             cipher: "Hello encrypted cipher".to_string(),
             iv: "iv".to_string(),
             message_sent_at: Some(NaiveDateTime::parse_from_str("2023-10-01T12:00:00", "%Y-%m-%dT%H:%M:%S").unwrap()),
+            client_id: None,
+            ttl: None,
+            expiration_date: None,
+            burn_after_read: false,
+            modification_date: None,
+            deletion_date: None,
+            reply_to: None,
+            reactions: BTreeMap::new(),
         };
 
         let data = Data::ChatMessage(chat);
@@ -102,7 +215,7 @@ mod tests {
         let group_key = GroupKey {
             encrypted_key: "some_key".to_string(),
             iv: "iv".to_string(),
-            creation_date: time.clone(),
+            creation_date: time,
             for_user_id: "user123".to_string(),
             from_user_id: "user=6789".to_string(),
         };
@@ -133,4 +246,20 @@ mod tests {
             panic!("Deserialization failed");
         }
     }
+
+    #[test]
+    fn test_message_from_json() {
+        let message = Message::from_json(r#"{"data":{"Ping":{"ping_type":"Ping"}},"version":2}"#).unwrap();
+
+        assert_eq!(message.data, Data::Ping(Ping::new(Knock::Ping)));
+        assert_eq!(
+            Message::from_json(r#"{"data":{"Hologram":{"depth":3}}}"#).unwrap_err(),
+            "Unsupported message type Hologram"
+        );
+        assert_eq!(
+            Message::from_json(r#"{"data":{"Ping":{"knock":"Ping"}}}"#).unwrap_err(),
+            "Cannot parse message"
+        );
+        assert_eq!(Message::from_json("not json").unwrap_err(), "Cannot parse message");
+    }
 }
@@ -1,5 +1,16 @@
+pub mod ack;
+pub mod attachment;
 pub mod chat_message;
 pub mod connection;
+pub mod delete_message;
+pub mod edit_message;
+pub mod envelope;
+pub mod error;
 pub mod group_key;
+pub mod hello;
 pub mod message;
 pub mod ping;
+pub mod purged;
+pub mod reaction;
+pub mod receipt;
+pub mod typing;
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/**
+ * `Purged` struct - Tells the room that messages are gone for good (expired or burned after reading),
+ * so clients delete their local copies as well. Only sent by the server.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Purged {
+    pub message_uuids: Vec<String>,
+}
+
+impl Purged {
+    pub const fn new(message_uuids: Vec<String>) -> Self {
+        Self { message_uuids }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Purged;
+
+    #[test]
+    fn test_purged_serialize() {
+        let purged = Purged::new(vec!["uuid123".to_string(), "uuid456".to_string()]);
+        let json = serde_json::to_string(&purged).unwrap();
+
+        assert_eq!(json, r#"{"message_uuids":["uuid123","uuid456"]}"#);
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/**
+ * `Reaction` struct - Adds or removes an emoji reaction of a chat member to a `ChatMessage`.
+ * Reactions are metadata and not encrypted. Each member can react with each emoji once per message,
+ * the server stamps `user_id` and relays the change to everyone in the room.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Reaction {
+    pub message_uuid: String,
+    pub user_id: String,
+    pub emoji: String,
+    pub action: Action,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Add,
+    Remove,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, Reaction};
+
+    #[test]
+    fn test_reaction_serialize() {
+        let reaction = Reaction {
+            message_uuid: "uuid123".to_string(),
+            user_id: "user123".to_string(),
+            emoji: "👍".to_string(),
+            action: Action::Add,
+        };
+        let json = serde_json::to_string(&reaction).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"message_uuid":"uuid123","user_id":"user123","emoji":"👍","action":"Add"}"#
+        );
+    }
+
+    #[test]
+    fn test_reaction_deserialize() {
+        let json = r#"{"message_uuid":"uuid123","user_id":"user123","emoji":"🎉","action":"Remove"}"#;
+        let reaction: Reaction = serde_json::from_str(json).unwrap();
+
+        assert_eq!(reaction.message_uuid, "uuid123".to_string());
+        assert_eq!(reaction.emoji, "🎉".to_string());
+        assert_eq!(reaction.action, Action::Remove);
+    }
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/**
+ * `Receipt` struct - Sent by a recipient once a `ChatMessage` arrived or got read.
+ * Only routed to the sender of the message, `Read` receipts also move the recipient's read pointer.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Receipt {
+    pub message_uuid: String,
+    pub kind: Kind,
+    pub user_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Delivered,
+    Read,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Kind, Receipt};
+
+    #[test]
+    fn test_receipt_serialize() {
+        let receipt = Receipt {
+            message_uuid: "uuid123".to_string(),
+            kind: Kind::Read,
+            user_id: "user123".to_string(),
+        };
+        let json = serde_json::to_string(&receipt).unwrap();
+
+        assert_eq!(json, r#"{"message_uuid":"uuid123","kind":"Read","user_id":"user123"}"#);
+    }
+
+    #[test]
+    fn test_receipt_deserialize() {
+        let json = r#"{"message_uuid":"uuid123","kind":"Delivered","user_id":"user123"}"#;
+        let receipt: Receipt = serde_json::from_str(json).unwrap();
+
+        assert_eq!(receipt.message_uuid, "uuid123".to_string());
+        assert_eq!(receipt.kind, Kind::Delivered);
+        assert_eq!(receipt.user_id, "user123".to_string());
+    }
+}
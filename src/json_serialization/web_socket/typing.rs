@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/**
+ * `Typing` struct - Ephemeral indicator that a participant started or stopped typing.
+ * Never stored, relayed to everyone in the room except the sending session and throttled per session.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Typing {
+    pub user_id: String,
+    pub is_typing: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Typing;
+
+    #[test]
+    fn test_typing_serialize() {
+        let typing = Typing {
+            user_id: "user123".to_string(),
+            is_typing: true,
+        };
+        let json = serde_json::to_string(&typing).unwrap();
+
+        assert_eq!(json, r#"{"user_id":"user123","is_typing":true}"#);
+    }
+
+    #[test]
+    fn test_typing_deserialize() {
+        let json = r#"{"user_id":"user123","is_typing":false}"#;
+        let typing: Typing = serde_json::from_str(json).unwrap();
+
+        assert_eq!(typing.user_id, "user123".to_string());
+        assert!(!typing.is_typing);
+    }
+}
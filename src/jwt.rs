@@ -2,6 +2,8 @@ use crate::helpers::env::get_int;
 use crate::json_serialization::response::item::Item;
 use crate::json_serialization::response::status::Status;
 use actix_web::dev::Payload;
+use actix_web::http::header::SEC_WEBSOCKET_PROTOCOL;
+use actix_web::web::Query;
 use actix_web::{FromRequest, HttpRequest, HttpResponse, ResponseError};
 use chrono::serde::ts_seconds;
 use chrono::{DateTime, Duration, TimeDelta, Utc};
@@ -13,6 +15,17 @@ use std::ops::Add;
 use std::{env, fmt};
 use uuid::Uuid;
 
+/**
+ * Subprotocol the server answers with during the WebSocket handshake.
+ * Clients passing their token via `Sec-WebSocket-Protocol` must offer it next to the token: `skumb, <token>`
+ */
+pub const WS_PROTOCOL: &str = "skumb";
+/**
+ * Subprotocol for sessions exchanging binary (`MessagePack`) envelopes instead of JSON text frames.
+ * Clients list the subprotocols in order of preference, the first one the server speaks is picked.
+ */
+pub const WS_BINARY_PROTOCOL: &str = "skumb.msgpack";
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct JwToken {
     pub user_uuid: Uuid,
@@ -106,15 +119,216 @@ impl ResponseError for UnauthorizedError {
     }
 }
 
+/**
+ * `WsTicket` - short-lived token bound to one chat.
+ * Browsers cannot set headers when opening a WebSocket, so this one is meant to be sent as `?ticket=` query parameter.
+ * It is not accepted as a session token (and vice versa) as both have different claims.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WsTicket {
+    pub user_uuid: Uuid,
+    pub chat_uuid: Uuid,
+    #[serde(with = "ts_seconds")]
+    pub exp: DateTime<Utc>,
+}
+
+impl WsTicket {
+    pub fn new(user_uuid: Uuid, chat_uuid: Uuid) -> Self {
+        Self {
+            user_uuid,
+            chat_uuid,
+            exp: Utc::now().add(get_ws_ticket_lifetime()),
+        }
+    }
+
+    pub fn encode(self) -> String {
+        let key = EncodingKey::from_secret(JwToken::get_key().as_ref());
+
+        encode(&Header::default(), &self, &key).expect("Ticket encoding failed")
+    }
+
+    pub fn from_ticket(ticket: &str) -> Option<Self> {
+        let key = DecodingKey::from_secret(JwToken::get_key().as_ref());
+
+        decode::<Self>(ticket, &key, &Validation::new(Algorithm::HS256))
+            .ok()
+            .map(|data| data.claims)
+    }
+}
+
+/**
+ * `GuestToken` - session of an anonymous guest, bound to the one chat they redeemed a share link for.
+ * It lives as long as a session token so guests can fetch new tickets, reconnect and leave the chat again.
+ * The identity is held as `guest_uuid` so it never passes as session token or ticket (and vice versa).
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GuestToken {
+    pub guest_uuid: Uuid,
+    pub chat_uuid: Uuid,
+    #[serde(with = "ts_seconds")]
+    pub minted: DateTime<Utc>,
+    #[serde(with = "ts_seconds")]
+    pub exp: DateTime<Utc>,
+}
+
+impl GuestToken {
+    pub fn new(guest_uuid: Uuid, chat_uuid: Uuid) -> Self {
+        let timestamp = Utc::now();
+
+        Self {
+            guest_uuid,
+            chat_uuid,
+            minted: timestamp,
+            exp: timestamp.add(get_session_lifetime()),
+        }
+    }
+
+    pub fn encode(self) -> String {
+        let key = EncodingKey::from_secret(JwToken::get_key().as_ref());
+
+        encode(&Header::default(), &self, &key).expect("Guest token encoding failed")
+    }
+
+    pub fn from_token(token: &str) -> Option<Self> {
+        let key = DecodingKey::from_secret(JwToken::get_key().as_ref());
+
+        decode::<Self>(token, &key, &Validation::new(Algorithm::HS256))
+            .ok()
+            .map(|data| data.claims)
+    }
+}
+
+/**
+ * `ShareToken` - invitation to a chat, handed out as share link.
+ * It doesn't expire on its own: expiry, usage limits and revocation are checked against the chat's `share_uri`.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShareToken {
+    pub chat_uuid: Uuid,
+    pub share_uri: String,
+}
+
+impl ShareToken {
+    pub const fn new(chat_uuid: Uuid, share_uri: String) -> Self {
+        Self { chat_uuid, share_uri }
+    }
+
+    pub fn encode(self) -> String {
+        let key = EncodingKey::from_secret(JwToken::get_key().as_ref());
+
+        encode(&Header::default(), &self, &key).expect("Share token encoding failed")
+    }
+
+    pub fn from_token(token: &str) -> Option<Self> {
+        let key = DecodingKey::from_secret(JwToken::get_key().as_ref());
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.required_spec_claims.clear();
+
+        decode::<Self>(token, &key, &validation).ok().map(|data| data.claims)
+    }
+}
+
+#[derive(Deserialize)]
+struct WsQuery {
+    ticket: Option<String>,
+}
+
+/**
+ * Resolves the caller of an endpoint of the given chat from the `token` header.
+ * Next to session tokens, guest tokens are accepted - but only for the chat they were handed out for.
+ */
+pub fn chat_caller_uuid(request: &HttpRequest, chat_uuid: Uuid) -> Result<Uuid, UnauthorizedError> {
+    let Some(header) = request.headers().get("token") else {
+        return Err(UnauthorizedError::new(
+            "Token not in header under key 'token'".to_string(),
+        ));
+    };
+    let raw_token = header
+        .to_str()
+        .map_err(|_| UnauthorizedError::new("Token cannot be decoded".to_string()))?;
+
+    session_uuid(raw_token, chat_uuid)
+}
+
+/**
+ * Decodes a session token or a guest token of the given chat.
+ * Guest tokens are tried first as failing session tokens are reported to sentry.
+ */
+fn session_uuid(raw_token: &str, chat_uuid: Uuid) -> Result<Uuid, UnauthorizedError> {
+    if let Some(guest_token) = GuestToken::from_token(raw_token) {
+        return if guest_token.chat_uuid == chat_uuid {
+            Ok(guest_token.guest_uuid)
+        } else {
+            Err(UnauthorizedError::new("Token is not valid for this chat".to_string()))
+        };
+    }
+
+    JwToken::from_token(raw_token).map_or_else(
+        || Err(UnauthorizedError::new("Token cannot be decoded".to_string())),
+        |token| Ok(token.user_uuid),
+    )
+}
+
+/**
+ * Resolves the user of a WebSocket upgrade request.
+ * Checked in order: `token` header, `Sec-WebSocket-Protocol` subprotocol (both session or guest token)
+ * and `ticket` query parameter.
+ * Returns `Ok(None)` if no credentials were sent at all (anonymous), but an error for invalid ones.
+ */
+pub fn user_uuid_from_ws_request(request: &HttpRequest, chat_uuid: Uuid) -> Result<Option<Uuid>, UnauthorizedError> {
+    if let Some(header) = request.headers().get("token") {
+        let raw_token = header
+            .to_str()
+            .map_err(|_| UnauthorizedError::new("Token cannot be decoded".to_string()))?;
+
+        return session_uuid(raw_token, chat_uuid).map(Some);
+    }
+
+    let protocol_token = request
+        .headers()
+        .get(SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|protocols| {
+            protocols
+                .split(',')
+                .map(str::trim)
+                .find(|protocol| !protocol.is_empty() && ![WS_PROTOCOL, WS_BINARY_PROTOCOL].contains(protocol))
+                .map(ToString::to_string)
+        });
+
+    if let Some(raw_token) = protocol_token {
+        return session_uuid(&raw_token, chat_uuid).map(Some);
+    }
+
+    let ticket = Query::<WsQuery>::from_query(request.query_string())
+        .ok()
+        .and_then(|query| query.into_inner().ticket);
+
+    ticket.map_or(Ok(None), |raw_ticket| match WsTicket::from_ticket(&raw_ticket) {
+        Some(ticket) if ticket.chat_uuid == chat_uuid => Ok(Some(ticket.user_uuid)),
+        Some(_) => Err(UnauthorizedError::new("Ticket is not valid for this chat".to_string())),
+        None => Err(UnauthorizedError::new("Ticket cannot be decoded".to_string())),
+    })
+}
+
 fn get_session_lifetime() -> TimeDelta {
     let lifetime_in_seconds = get_int("SESSION_LIFETIME");
 
     Duration::try_seconds(i64::from(lifetime_in_seconds)).expect("Duration calculation failed for token expiring")
 }
 
+fn get_ws_ticket_lifetime() -> TimeDelta {
+    let lifetime_in_seconds = get_int("WS_TICKET_LIFETIME");
+
+    Duration::try_seconds(i64::from(lifetime_in_seconds)).expect("Duration calculation failed for ticket expiring")
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{get_session_lifetime, JwToken, UnauthorizedError};
+    use super::{
+        chat_caller_uuid, get_session_lifetime, user_uuid_from_ws_request, GuestToken, JwToken, ShareToken,
+        UnauthorizedError, WsTicket, WS_BINARY_PROTOCOL, WS_PROTOCOL,
+    };
     use actix_web::dev::Payload;
     use actix_web::http::header::{HeaderName, HeaderValue};
     use actix_web::{http, test, FromRequest, ResponseError};
@@ -333,4 +547,186 @@ mod tests {
 
         assert_eq!(response.status().as_str(), http::StatusCode::UNAUTHORIZED.as_str());
     }
+
+    #[test]
+    async fn ws_ticket_encode_decode() {
+        temp_env::with_vars(
+            [("APP_SECRET", Some("test_secret")), ("WS_TICKET_LIFETIME", Some("30"))],
+            || {
+                let user_uuid = Uuid::new_v4();
+                let chat_uuid = Uuid::new_v4();
+                let encoded = WsTicket::new(user_uuid, chat_uuid).encode();
+
+                let ticket = WsTicket::from_ticket(&encoded).expect("Ticket decoding failed");
+                assert_eq!(ticket.user_uuid, user_uuid);
+                assert_eq!(ticket.chat_uuid, chat_uuid);
+
+                // A ticket must never be usable as session token
+                assert!(JwToken::from_token(&encoded).is_none());
+            },
+        );
+    }
+
+    #[test]
+    async fn ws_request_without_credentials() {
+        let request = test::TestRequest::default().to_http_request();
+
+        assert!(user_uuid_from_ws_request(&request, Uuid::new_v4()).unwrap().is_none());
+    }
+
+    #[test]
+    async fn ws_request_token_header_and_protocol() {
+        temp_env::with_vars(
+            [("APP_SECRET", Some("test_secret")), ("SESSION_LIFETIME", Some("3600"))],
+            || {
+                let uuid = Uuid::new_v4();
+                let token = JwToken::new(uuid).encode();
+
+                let request = test::TestRequest::default()
+                    .insert_header(("token", token.as_str()))
+                    .to_http_request();
+                assert_eq!(user_uuid_from_ws_request(&request, Uuid::new_v4()).unwrap(), Some(uuid));
+
+                let request = test::TestRequest::default()
+                    .insert_header(("Sec-WebSocket-Protocol", format!("{WS_PROTOCOL}, {token}")))
+                    .to_http_request();
+                assert_eq!(user_uuid_from_ws_request(&request, Uuid::new_v4()).unwrap(), Some(uuid));
+
+                let request = test::TestRequest::default()
+                    .insert_header((
+                        "Sec-WebSocket-Protocol",
+                        format!("{WS_BINARY_PROTOCOL}, {WS_PROTOCOL}, {token}"),
+                    ))
+                    .to_http_request();
+                assert_eq!(user_uuid_from_ws_request(&request, Uuid::new_v4()).unwrap(), Some(uuid));
+
+                let request = test::TestRequest::default()
+                    .insert_header(("Sec-WebSocket-Protocol", format!("{WS_PROTOCOL}, invalid_token")))
+                    .to_http_request();
+                assert!(user_uuid_from_ws_request(&request, Uuid::new_v4()).is_err());
+            },
+        );
+    }
+
+    #[test]
+    async fn ws_request_ticket() {
+        temp_env::with_vars(
+            [("APP_SECRET", Some("test_secret")), ("WS_TICKET_LIFETIME", Some("30"))],
+            || {
+                let user_uuid = Uuid::new_v4();
+                let chat_uuid = Uuid::new_v4();
+                let ticket = WsTicket::new(user_uuid, chat_uuid).encode();
+
+                let request =
+                    test::TestRequest::with_uri(&format!("/ws/{chat_uuid}?ticket={ticket}")).to_http_request();
+                assert_eq!(user_uuid_from_ws_request(&request, chat_uuid).unwrap(), Some(user_uuid));
+
+                // Bound to the chat it was minted for
+                match user_uuid_from_ws_request(&request, Uuid::new_v4()) {
+                    Ok(_) => panic!("Ticket must not be valid for other chats"),
+                    Err(err) => assert_eq!(err.message, "Ticket is not valid for this chat".to_string()),
+                }
+            },
+        );
+    }
+
+    #[test]
+    async fn guest_token_encode_decode() {
+        temp_env::with_vars(
+            [("APP_SECRET", Some("test_secret")), ("SESSION_LIFETIME", Some("3600"))],
+            || {
+                let guest_uuid = Uuid::new_v4();
+                let chat_uuid = Uuid::new_v4();
+                let encoded = GuestToken::new(guest_uuid, chat_uuid).encode();
+
+                let guest_token = GuestToken::from_token(&encoded).expect("Guest token decoding failed");
+                assert_eq!(guest_token.guest_uuid, guest_uuid);
+                assert_eq!(guest_token.chat_uuid, chat_uuid);
+                assert!(guest_token.exp > guest_token.minted);
+
+                // Neither a session token nor a ticket
+                assert!(JwToken::from_token(&encoded).is_none());
+                assert!(WsTicket::from_ticket(&encoded).is_none());
+                assert!(GuestToken::from_token(&JwToken::new(guest_uuid).encode()).is_none());
+            },
+        );
+    }
+
+    #[test]
+    async fn guest_reconnects_with_guest_token() {
+        temp_env::with_vars(
+            [
+                ("APP_SECRET", Some("test_secret")),
+                ("SESSION_LIFETIME", Some("3600")),
+                ("WS_TICKET_LIFETIME", Some("30")),
+            ],
+            || {
+                let guest_uuid = Uuid::new_v4();
+                let chat_uuid = Uuid::new_v4();
+                let guest_token = GuestToken::new(guest_uuid, chat_uuid).encode();
+
+                // New tickets are minted for the same guest, long after the first one expired
+                let request = test::TestRequest::default()
+                    .insert_header(("token", guest_token.as_str()))
+                    .to_http_request();
+                assert_eq!(chat_caller_uuid(&request, chat_uuid).unwrap(), guest_uuid);
+
+                let ticket = WsTicket::new(chat_caller_uuid(&request, chat_uuid).unwrap(), chat_uuid).encode();
+                let request =
+                    test::TestRequest::with_uri(&format!("/ws/{chat_uuid}?ticket={ticket}")).to_http_request();
+                assert_eq!(
+                    user_uuid_from_ws_request(&request, chat_uuid).unwrap(),
+                    Some(guest_uuid)
+                );
+
+                // Or the socket is opened with the guest token directly
+                let request = test::TestRequest::default()
+                    .insert_header(("Sec-WebSocket-Protocol", format!("{WS_PROTOCOL}, {guest_token}")))
+                    .to_http_request();
+                assert_eq!(
+                    user_uuid_from_ws_request(&request, chat_uuid).unwrap(),
+                    Some(guest_uuid)
+                );
+
+                // Bound to the chat the share link was redeemed for
+                let request = test::TestRequest::default()
+                    .insert_header(("token", guest_token.as_str()))
+                    .to_http_request();
+                match chat_caller_uuid(&request, Uuid::new_v4()) {
+                    Ok(_) => panic!("Guest token must not be valid for other chats"),
+                    Err(err) => assert_eq!(err.message, "Token is not valid for this chat".to_string()),
+                }
+                assert!(user_uuid_from_ws_request(&request, Uuid::new_v4()).is_err());
+            },
+        );
+    }
+
+    #[test]
+    async fn chat_caller_without_token() {
+        let request = test::TestRequest::default().to_http_request();
+
+        match chat_caller_uuid(&request, Uuid::new_v4()) {
+            Ok(_) => panic!("A caller without token must be rejected"),
+            Err(err) => assert_eq!(err.message, "Token not in header under key 'token'".to_string()),
+        }
+    }
+
+    #[test]
+    async fn share_token_encode_decode() {
+        temp_env::with_vars(
+            [("APP_SECRET", Some("test_secret")), ("WS_TICKET_LIFETIME", Some("30"))],
+            || {
+                let chat_uuid = Uuid::new_v4();
+                let encoded = ShareToken::new(chat_uuid, "share_uri".to_string()).encode();
+                let share_token = ShareToken::from_token(&encoded).expect("Share token decoding failed");
+
+                assert_eq!(share_token.chat_uuid, chat_uuid);
+                assert_eq!(share_token.share_uri, "share_uri".to_string());
+
+                // Neither tampered nor other tokens are accepted
+                assert!(ShareToken::from_token(&format!("{encoded}x")).is_none());
+                assert!(ShareToken::from_token(&WsTicket::new(Uuid::new_v4(), chat_uuid).encode()).is_none());
+            },
+        );
+    }
 }
@@ -1,21 +1,37 @@
+mod blob_store;
 mod database;
 mod helpers;
 mod json_serialization;
 mod jwt;
 mod models;
+mod purge_actor;
 mod schema;
 mod views;
 mod ws_actor;
 
-use crate::helpers::env::get_float;
+use crate::blob_store::local::LocalBlobStore;
+use crate::blob_store::BlobStore;
+use crate::database::DB;
+use crate::helpers::env::{get_float, get_int};
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
 use crate::json_serialization::response::item::Item as ResponseItem;
 use crate::json_serialization::response::status::Status;
+use crate::json_serialization::web_socket::envelope::Encoding;
+use crate::jwt::{user_uuid_from_ws_request, UnauthorizedError, WS_BINARY_PROTOCOL, WS_PROTOCOL};
+use crate::models::chat::item::fetch as fetch_chat;
+use crate::models::chat_member::item::fetch as fetch_member;
+use crate::models::chat_member::role::Role;
+use crate::ws_actor::{IsBanned, Requester};
 use actix::{Actor, Addr};
 use actix_cors::Cors;
+use actix_web::http::header;
 use actix_web::middleware::Logger;
 use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
 use actix_web_actors::ws;
 use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
 use uuid::Uuid;
 
 #[actix_web::main]
@@ -24,7 +40,11 @@ async fn main() -> std::io::Result<()> {
 
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
 
+    let blob_store: Arc<dyn BlobStore> = Arc::new(LocalBlobStore::new(PathBuf::from(
+        env::var("ATTACHMENT_DIR").expect("ATTACHMENT_DIR not set"),
+    )));
     let chat_server = ws_actor::ChatServer::new().start();
+    purge_actor::Purger::new(chat_server.clone(), blob_store.clone()).start();
 
     let server = HttpServer::new(move || {
         // Handling CORS issues
@@ -35,6 +55,7 @@ async fn main() -> std::io::Result<()> {
             .wrap(Logger::new("%a %{User-Agent}i %r %s %D"))
             .wrap(cors)
             .app_data(web::Data::new(chat_server.clone()))
+            .app_data(web::Data::from(blob_store.clone()))
             // Websocket in general
             .service(web::resource("/ws/{chat_uuid}").route(web::get().to(ws_index)))
             // Api routes
@@ -69,22 +90,75 @@ async fn ws_index(
     request: HttpRequest,
     stream: web::Payload,
     srv: web::Data<Addr<ws_actor::ChatServer>>,
-    path: Option<web::Path<Uuid>>,
+    path: web::Path<Uuid>,
+    db: DB,
+    db2: DB,
 ) -> Result<HttpResponse, actix_web::Error> {
-    let chat_uuid = path.map_or_else(Uuid::new_v4, web::Path::into_inner);
-
-    // Todo: getting user_uuid from jwt auth
-    let user_uuid = Uuid::new_v4();
-
-    ws::start(
-        ws_actor::MyWs {
+    let chat_uuid = path.into_inner();
+
+    // Sockets are only opened for existing (not deleted) chats
+    let Some(chat) = fetch_chat(chat_uuid, db).into_iter().next() else {
+        return Err(ApiError::new(ErrorCode::NotFound, "Error during chat lookup".to_string()).into());
+    };
+
+    let credential = user_uuid_from_ws_request(&request, chat_uuid)?;
+    let user_uuid = match credential {
+        Some(user_uuid) => user_uuid,
+        // Anonymous users only get a random identity if the chat explicitly allows it
+        None if chat.allow_anonymous => Uuid::new_v4(),
+        None => {
+            return Err(UnauthorizedError::new(
+                "Token missing: send it as 'token' header, subprotocol or 'ticket' query parameter".to_string(),
+            )
+            .into())
+        }
+    };
+
+    // Anonymous visitors get a new identity with every connection, so they are held back by their address instead
+    let requester = match (credential, request.peer_addr()) {
+        (None, Some(address)) => Requester::Address(address.ip()),
+        _ => Requester::User(user_uuid),
+    };
+
+    // Reconnecting doesn't lift a ban for exceeding the rate limit repeatedly
+    if srv.send(IsBanned { requester }).await.unwrap_or(false) {
+        return Err(ApiError::new(
+            ErrorCode::TooManyRequests,
+            "Rate limit exceeded repeatedly, try again later".to_string(),
+        )
+        .into());
+    }
+
+    // Non-members are rejected by the chat server; credential-less visitors of anonymous chats only observe,
+    // writing takes a redeemed share link - and removed users cannot come back with their token
+    let role = match fetch_member(chat_uuid, user_uuid, db2).first() {
+        Some(member) => Some(member.role()),
+        None if credential.is_none() && chat.allow_anonymous => Some(Role::ReadOnly),
+        None => None,
+    };
+    let encoding = Encoding::negotiate(
+        request
+            .headers()
+            .get(header::SEC_WEBSOCKET_PROTOCOL)
+            .and_then(|protocols| protocols.to_str().ok()),
+    );
+
+    ws::WsResponseBuilder::new(
+        ws_actor::MyWs::new(
             chat_uuid,
             user_uuid,
-            users: srv.get_ref().clone(),
-        },
+            requester,
+            role,
+            chat.default_ttl,
+            encoding,
+            srv.get_ref().clone(),
+        ),
         &request,
         stream,
     )
+    .protocols(&[WS_BINARY_PROTOCOL, WS_PROTOCOL])
+    .frame_size(usize::try_from(get_int("WS_MAX_FRAME_SIZE")).unwrap_or(usize::MAX))
+    .start()
 }
 
 #[allow(clippy::future_not_send)]
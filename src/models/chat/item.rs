@@ -0,0 +1,162 @@
+use crate::database::DB;
+use crate::models::chat::retention::Retention;
+use crate::models::chat_member::role::Role;
+use crate::schema::{chat_members, chats};
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, Identifiable, QueryDsl, Queryable, RunQueryDsl};
+use uuid::Uuid;
+
+#[derive(Queryable, Clone, Identifiable)]
+#[diesel(table_name = chats)]
+pub struct Chat {
+    pub id: i32,
+    pub creator_id: i32,
+    pub uuid: Uuid,
+    pub name: String,
+    pub share_uri: Option<String>,
+    pub creation_date: NaiveDateTime,
+    pub modification_date: Option<NaiveDateTime>,
+    pub deletion_date: Option<NaiveDateTime>,
+    pub allow_anonymous: bool,
+    pub share_uri_expiration_date: Option<NaiveDateTime>,
+    pub share_uri_max_uses: Option<i32>,
+    pub share_uri_uses: i32,
+    /// Seconds messages live for unless their sender chose otherwise - forever without
+    pub default_ttl: Option<i32>,
+    pub retention_policy: String,
+    pub retention_days: Option<i32>,
+}
+
+impl Chat {
+    pub fn retention(&self) -> Retention {
+        Retention::from_columns(&self.retention_policy, self.retention_days)
+    }
+}
+
+pub const ALL_ROLES: [Role; 4] = [Role::Owner, Role::Admin, Role::Member, Role::ReadOnly];
+
+/**
+ * Loads the chat if it isn't (soft) deleted - regardless of its members
+ */
+pub fn fetch(uuid: Uuid, mut db: DB) -> Vec<Chat> {
+    // Loading it from DB
+    chats::table
+        .filter(chats::columns::uuid.eq(uuid))
+        .filter(chats::columns::deletion_date.is_null())
+        .order(chats::columns::id.asc())
+        .load::<Chat>(&mut db.connection)
+        .unwrap()
+}
+
+/**
+ * Loads the chat only if it isn't (soft) deleted and the user is a member with one of the given roles
+ */
+pub fn fetch_for_roles(uuid: Uuid, user_uuid: Uuid, roles: &[Role], mut db: DB) -> Vec<Chat> {
+    let roles: Vec<String> = roles.iter().map(|role| role.stringify()).collect();
+
+    chats::table
+        .filter(chats::columns::uuid.eq(uuid))
+        .filter(chats::columns::deletion_date.is_null())
+        .filter(
+            chats::columns::id.eq_any(
+                chat_members::table
+                    .filter(chat_members::columns::user_uuid.eq(user_uuid))
+                    .filter(chat_members::columns::role.eq_any(roles))
+                    .select(chat_members::columns::chat_id),
+            ),
+        )
+        .order(chats::columns::id.asc())
+        .load::<Chat>(&mut db.connection)
+        .unwrap()
+}
+
+/**
+ * Loads the chat only if it isn't (soft) deleted and the user is a member of it (in any role)
+ */
+pub fn fetch_for_member(uuid: Uuid, user_uuid: Uuid, db: DB) -> Vec<Chat> {
+    fetch_for_roles(uuid, user_uuid, &ALL_ROLES, db)
+}
+
+/**
+ * Soft deletes the chat by setting its deletion date - only owners may do so
+ */
+pub fn delete(uuid: Uuid, user_uuid: Uuid, mut db: DB) -> Option<Uuid> {
+    let results = chats::table
+        .filter(chats::columns::uuid.eq(uuid))
+        .filter(chats::columns::deletion_date.is_null())
+        .filter(
+            chats::columns::id.eq_any(
+                chat_members::table
+                    .filter(chat_members::columns::user_uuid.eq(user_uuid))
+                    .filter(chat_members::columns::role.eq(Role::Owner.stringify()))
+                    .select(chat_members::columns::chat_id),
+            ),
+        );
+
+    match diesel::update(results)
+        .set(chats::columns::deletion_date.eq(chrono::Utc::now().naive_utc()))
+        .execute(&mut db.connection)
+    {
+        Ok(exec) => {
+            if exec > 0 {
+                return Some(uuid);
+            }
+
+            None
+        }
+        Err(error) => {
+            // Logging a bit
+            sentry::capture_error(&error);
+
+            None
+        }
+    }
+}
+
+/**
+ * Edits the chat - only owners and admins may do so.
+ * Default TTL and retention are only touched if given, `Some(None)` clears the default TTL.
+ */
+#[allow(clippy::option_option)]
+pub fn edit(
+    uuid: Uuid,
+    user_uuid: Uuid,
+    name: String,
+    allow_anonymous: bool,
+    default_ttl: Option<Option<i32>>,
+    retention: Option<Retention>,
+    mut db: DB,
+) -> Vec<Chat> {
+    let managing_roles = [Role::Owner, Role::Admin];
+    let results = chats::table
+        .filter(chats::columns::uuid.eq(uuid))
+        .filter(chats::columns::deletion_date.is_null())
+        .filter(
+            chats::columns::id.eq_any(
+                chat_members::table
+                    .filter(chat_members::columns::user_uuid.eq(user_uuid))
+                    .filter(chat_members::columns::role.eq_any(managing_roles.iter().map(|role| role.stringify())))
+                    .select(chat_members::columns::chat_id),
+            ),
+        );
+    let exec = diesel::update(results)
+        .set((
+            chats::columns::name.eq(name),
+            chats::columns::allow_anonymous.eq(allow_anonymous),
+            default_ttl.map(|default_ttl| chats::columns::default_ttl.eq(default_ttl)),
+            retention.map(|retention| {
+                (
+                    chats::columns::retention_policy.eq(retention.policy()),
+                    chats::columns::retention_days.eq(retention.days()),
+                )
+            }),
+            chats::columns::modification_date.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(&mut db.connection);
+
+    if let Err(error) = exec {
+        sentry::capture_error(&error);
+    }
+
+    fetch_for_roles(uuid, user_uuid, &managing_roles, db)
+}
@@ -0,0 +1,23 @@
+use crate::database::DB;
+use crate::models::chat::item::Chat;
+use crate::schema::{chat_members, chats};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+/**
+ * Loads all (not deleted) chats the user is a member of
+ */
+pub fn fetch(user_uuid: Uuid, count: Option<i64>, mut db: DB) -> Vec<Chat> {
+    // Loading it from DB
+    let limit: i64 = count.unwrap_or(100);
+
+    chats::table
+        .inner_join(chat_members::table)
+        .filter(chat_members::columns::user_uuid.eq(user_uuid))
+        .filter(chats::columns::deletion_date.is_null())
+        .select(chats::all_columns)
+        .limit(limit)
+        .order(chats::columns::id.asc())
+        .load::<Chat>(&mut db.connection)
+        .unwrap()
+}
@@ -0,0 +1,6 @@
+pub mod item;
+pub mod items;
+pub mod new_item;
+pub mod purge;
+pub mod retention;
+pub mod share;
@@ -0,0 +1,96 @@
+use crate::database::DB;
+use crate::models::chat::item::{fetch, Chat};
+use crate::models::chat::retention::Retention;
+use crate::models::chat_member::new_item::NewChatMember;
+use crate::models::chat_member::role::Role;
+use crate::schema::{chat_members, chats, users};
+use diesel::{Connection, ExpressionMethods, Insertable, OptionalExtension, QueryDsl, RunQueryDsl};
+use serde::Serialize;
+use serde_json::json;
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+#[derive(Insertable, Clone, Debug, Serialize)]
+#[diesel(table_name = chats)]
+pub struct NewChat {
+    pub creator_id: i32,
+    pub uuid: Uuid,
+    pub name: String,
+    pub allow_anonymous: bool,
+    pub default_ttl: Option<i32>,
+    pub retention_policy: String,
+    pub retention_days: Option<i32>,
+}
+
+impl NewChat {
+    pub fn new(
+        creator_id: i32,
+        uuid: Uuid,
+        name: String,
+        allow_anonymous: bool,
+        default_ttl: Option<i32>,
+        retention: Retention,
+    ) -> Self {
+        Self {
+            creator_id,
+            uuid,
+            name,
+            allow_anonymous,
+            default_ttl,
+            retention_policy: retention.policy(),
+            retention_days: retention.days(),
+        }
+    }
+}
+
+impl Display for NewChat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", json!(self))
+    }
+}
+
+pub fn create_item(
+    name: String,
+    allow_anonymous: bool,
+    default_ttl: Option<i32>,
+    retention: Retention,
+    creator_uuid: Uuid,
+    mut db: DB,
+) -> Vec<Chat> {
+    let creator_id = users::table
+        .filter(users::columns::uuid.eq(creator_uuid))
+        .select(users::columns::id)
+        .first::<i32>(&mut db.connection)
+        .optional();
+
+    let creator_id = match creator_id {
+        Ok(Some(creator_id)) => creator_id,
+        Ok(None) => return vec![],
+        Err(error) => {
+            sentry::capture_error(&error);
+
+            return vec![];
+        }
+    };
+
+    let uuid = Uuid::new_v4();
+    let new_item = NewChat::new(creator_id, uuid, name, allow_anonymous, default_ttl, retention);
+
+    // The creator becomes the owner of the chat
+    let exec = db.connection.transaction::<_, diesel::result::Error, _>(|connection| {
+        let chat_id = diesel::insert_into(chats::table)
+            .values(&new_item)
+            .returning(chats::columns::id)
+            .get_result::<i32>(connection)?;
+
+        diesel::insert_into(chat_members::table)
+            .values(&NewChatMember::new(chat_id, creator_uuid, Role::Owner))
+            .execute(connection)
+    });
+
+    if let Err(error) = exec {
+        sentry::capture_error(&error);
+    }
+
+    fetch(uuid, db)
+}
@@ -0,0 +1,59 @@
+use crate::database::DB;
+use crate::models::purge_record::new_item::create_item as create_purge_record;
+use crate::models::purge_record::reason::Reason;
+use crate::schema::{chat_attachments, chat_group_keys, chat_members, chat_messages, chats};
+use chrono::NaiveDateTime;
+use diesel::{Connection, ExpressionMethods, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+/**
+ * Deletes the chats soft deleted before the given date for good - with all their messages, members, group keys and
+ * attachments (their blobs are up to the caller).
+ * Each one is recorded within the same transaction. Returns the uuids of the deleted chats.
+ */
+pub fn delete_soft_deleted(deleted_before: NaiveDateTime, mut db: DB) -> Vec<Uuid> {
+    let chats = chats::table
+        .filter(chats::columns::deletion_date.lt(deleted_before))
+        .select((chats::columns::id, chats::columns::uuid))
+        .load::<(i32, Uuid)>(&mut db.connection)
+        .unwrap();
+
+    chats
+        .into_iter()
+        .filter_map(|(chat_id, chat_uuid)| {
+            let exec = db.connection.transaction::<_, diesel::result::Error, _>(|connection| {
+                let message_uuids =
+                    diesel::delete(chat_messages::table.filter(chat_messages::columns::chat_id.eq(chat_id)))
+                        .returning(chat_messages::columns::uuid)
+                        .get_results::<Uuid>(connection)?;
+
+                diesel::delete(chat_group_keys::table.filter(chat_group_keys::columns::chat_id.eq(chat_id)))
+                    .execute(connection)?;
+                let attachment_uuids =
+                    diesel::delete(chat_attachments::table.filter(chat_attachments::columns::chat_id.eq(chat_id)))
+                        .returning(chat_attachments::columns::uuid)
+                        .get_results::<Uuid>(connection)?;
+                diesel::delete(chat_members::table.filter(chat_members::columns::chat_id.eq(chat_id)))
+                    .execute(connection)?;
+                diesel::delete(chats::table.filter(chats::columns::id.eq(chat_id))).execute(connection)?;
+
+                create_purge_record(
+                    chat_uuid,
+                    Reason::ChatDeleted,
+                    &message_uuids,
+                    &attachment_uuids,
+                    connection,
+                )
+            });
+
+            match exec {
+                Ok(_) => Some(chat_uuid),
+                Err(error) => {
+                    sentry::capture_error(&error);
+
+                    None
+                }
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/**
+ * `Retention` of a chat's messages - on top of the TTL of each message.
+ * Stored as policy (its string representation) and the number of days in `chats`.
+ ** `Forever` keeps them until they expire or the chat gets deleted.
+ ** `Days` purges them once they are older than the given number of days.
+ ** `DeleteOnEmpty` purges them all once the last member left the chat (or got removed from it).
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Retention {
+    #[default]
+    Forever,
+    Days(i32),
+    DeleteOnEmpty,
+}
+
+impl Retention {
+    pub fn from_columns(policy: &str, days: Option<i32>) -> Self {
+        // The database constraints only allow known policies (with days for `Days`), keeping them otherwise
+        match (policy, days) {
+            ("Days", Some(days)) => Self::Days(days),
+            ("DeleteOnEmpty", _) => Self::DeleteOnEmpty,
+            _ => Self::Forever,
+        }
+    }
+
+    pub fn policy(self) -> String {
+        match self {
+            Self::Forever => "Forever".to_string(),
+            Self::Days(_) => "Days".to_string(),
+            Self::DeleteOnEmpty => "DeleteOnEmpty".to_string(),
+        }
+    }
+
+    pub const fn days(self) -> Option<i32> {
+        match self {
+            Self::Days(days) => Some(days),
+            Self::Forever | Self::DeleteOnEmpty => None,
+        }
+    }
+
+    pub const fn is_valid(self) -> bool {
+        !matches!(self, Self::Days(days) if days <= 0)
+    }
+}
+
+impl fmt::Display for Retention {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", &self.policy())
+    }
+}
+
+#[cfg(test)]
+mod chat_retention_tests {
+    use super::Retention;
+
+    #[test]
+    fn columns() {
+        for retention in [Retention::Forever, Retention::Days(30), Retention::DeleteOnEmpty] {
+            assert_eq!(
+                Retention::from_columns(&retention.policy(), retention.days()),
+                retention
+            );
+        }
+
+        assert_eq!(Retention::from_columns("Days", None), Retention::Forever);
+        assert_eq!(Retention::from_columns("Unknown", Some(1)), Retention::Forever);
+    }
+
+    #[test]
+    fn is_valid() {
+        assert!(Retention::Forever.is_valid());
+        assert!(Retention::Days(1).is_valid());
+        assert!(!Retention::Days(0).is_valid());
+        assert!(!Retention::Days(-1).is_valid());
+        assert!(Retention::DeleteOnEmpty.is_valid());
+    }
+
+    #[test]
+    fn serialize() {
+        assert_eq!(serde_json::to_string(&Retention::Forever).unwrap(), r#""Forever""#);
+        assert_eq!(serde_json::to_string(&Retention::Days(7)).unwrap(), r#"{"Days":7}"#);
+        assert_eq!(
+            serde_json::from_str::<Retention>(r#""DeleteOnEmpty""#).unwrap(),
+            Retention::DeleteOnEmpty
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use crate::database::DB;
+use crate::models::chat::item::{fetch_for_roles, Chat};
+use crate::models::chat_member::item::{fetch as fetch_member, ChatMember};
+use crate::models::chat_member::new_item::NewChatMember;
+use crate::models::chat_member::role::Role;
+use crate::schema::{chat_members, chats};
+use chrono::NaiveDateTime;
+use diesel::result::Error as DieselError;
+use diesel::{BoolExpressionMethods, Connection, ExpressionMethods, NullableExpressionMethods, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+const MANAGING_ROLES: [Role; 2] = [Role::Owner, Role::Admin];
+
+/**
+ * Sets a new share uri (replacing and thereby revoking the previous one) - only owners and admins may do so
+ */
+pub fn create(
+    uuid: Uuid,
+    user_uuid: Uuid,
+    share_uri: String,
+    max_uses: Option<i32>,
+    expiration_date: Option<NaiveDateTime>,
+    mut db: DB,
+) -> Vec<Chat> {
+    let results = chats::table
+        .filter(chats::columns::uuid.eq(uuid))
+        .filter(chats::columns::deletion_date.is_null())
+        .filter(
+            chats::columns::id.eq_any(
+                chat_members::table
+                    .filter(chat_members::columns::user_uuid.eq(user_uuid))
+                    .filter(chat_members::columns::role.eq_any(MANAGING_ROLES.iter().map(|role| role.stringify())))
+                    .select(chat_members::columns::chat_id),
+            ),
+        );
+    let exec = diesel::update(results)
+        .set((
+            chats::columns::share_uri.eq(share_uri),
+            chats::columns::share_uri_expiration_date.eq(expiration_date),
+            chats::columns::share_uri_max_uses.eq(max_uses),
+            chats::columns::share_uri_uses.eq(0),
+        ))
+        .execute(&mut db.connection);
+
+    if let Err(error) = exec {
+        sentry::capture_error(&error);
+    }
+
+    fetch_for_roles(uuid, user_uuid, &MANAGING_ROLES, db)
+}
+
+/**
+ * Removes the share uri so no invitation can be redeemed anymore - only owners and admins may do so
+ */
+pub fn revoke(uuid: Uuid, user_uuid: Uuid, mut db: DB) -> Option<Uuid> {
+    let results = chats::table
+        .filter(chats::columns::uuid.eq(uuid))
+        .filter(chats::columns::deletion_date.is_null())
+        .filter(chats::columns::share_uri.is_not_null())
+        .filter(
+            chats::columns::id.eq_any(
+                chat_members::table
+                    .filter(chat_members::columns::user_uuid.eq(user_uuid))
+                    .filter(chat_members::columns::role.eq_any(MANAGING_ROLES.iter().map(|role| role.stringify())))
+                    .select(chat_members::columns::chat_id),
+            ),
+        );
+
+    match diesel::update(results)
+        .set((
+            chats::columns::share_uri.eq(None::<String>),
+            chats::columns::share_uri_expiration_date.eq(None::<NaiveDateTime>),
+            chats::columns::share_uri_max_uses.eq(None::<i32>),
+            chats::columns::share_uri_uses.eq(0),
+        ))
+        .execute(&mut db.connection)
+    {
+        Ok(exec) => {
+            if exec > 0 {
+                return Some(uuid);
+            }
+
+            None
+        }
+        Err(error) => {
+            // Logging a bit
+            sentry::capture_error(&error);
+
+            None
+        }
+    }
+}
+
+/**
+ * Uses up one redemption of the share uri and adds the user as member - both or nothing.
+ * Returns nothing if the share uri is unknown, revoked, expired or used up.
+ */
+pub fn redeem(uuid: Uuid, share_uri: &str, user_uuid: Uuid, mut db: DB) -> Vec<ChatMember> {
+    let now = chrono::Utc::now().naive_utc();
+
+    let exec = db.connection.transaction::<_, DieselError, _>(|connection| {
+        let results = chats::table
+            .filter(chats::columns::uuid.eq(uuid))
+            .filter(chats::columns::deletion_date.is_null())
+            .filter(chats::columns::share_uri.eq(share_uri))
+            .filter(
+                chats::columns::share_uri_expiration_date
+                    .is_null()
+                    .or(chats::columns::share_uri_expiration_date.gt(now)),
+            )
+            .filter(
+                chats::columns::share_uri_max_uses
+                    .is_null()
+                    .or(chats::columns::share_uri_uses
+                        .nullable()
+                        .lt(chats::columns::share_uri_max_uses)),
+            );
+
+        let chat_id = diesel::update(results)
+            .set(chats::columns::share_uri_uses.eq(chats::columns::share_uri_uses + 1))
+            .returning(chats::columns::id)
+            .get_result::<i32>(connection)?;
+
+        diesel::insert_into(chat_members::table)
+            .values(&NewChatMember::new(chat_id, user_uuid, Role::Member))
+            .execute(connection)
+    });
+
+    match exec {
+        Ok(_) => fetch_member(uuid, user_uuid, db),
+        Err(DieselError::NotFound) => vec![],
+        Err(error) => {
+            sentry::capture_error(&error);
+
+            vec![]
+        }
+    }
+}
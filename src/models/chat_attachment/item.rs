@@ -0,0 +1,39 @@
+use crate::database::DB;
+use crate::schema::{chat_attachments, chats};
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, Identifiable, QueryDsl, Queryable, RunQueryDsl};
+use uuid::Uuid;
+
+#[derive(Queryable, Clone, Identifiable)]
+#[diesel(table_name = chat_attachments)]
+pub struct ChatAttachment {
+    pub id: i32,
+    pub chat_id: i32,
+    pub uuid: Uuid,
+    pub uploader_uuid: Uuid,
+    /// Of the ciphertext, in bytes
+    pub size: i64,
+    /// Hex encoded SHA-256 of the ciphertext
+    pub content_hash: String,
+    pub creation_date: NaiveDateTime,
+    /// Taken from the default TTL of the chat at upload
+    pub expiration_date: Option<NaiveDateTime>,
+}
+
+/**
+ * Loads the attachment only if it belongs to the chat (and the chat isn't soft deleted)
+ */
+pub fn fetch(chat_uuid: Uuid, uuid: Uuid, mut db: DB) -> Vec<ChatAttachment> {
+    chat_attachments::table
+        .filter(chat_attachments::columns::uuid.eq(uuid))
+        .filter(
+            chat_attachments::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .filter(chats::columns::deletion_date.is_null())
+                    .select(chats::columns::id),
+            ),
+        )
+        .load::<ChatAttachment>(&mut db.connection)
+        .unwrap()
+}
@@ -0,0 +1,72 @@
+use crate::database::DB;
+use crate::models::chat_attachment::item::ChatAttachment;
+use crate::schema::{chat_attachments, chats};
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, Insertable, OptionalExtension, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = chat_attachments)]
+pub struct NewChatAttachment {
+    pub chat_id: i32,
+    pub uuid: Uuid,
+    pub uploader_uuid: Uuid,
+    pub size: i64,
+    pub content_hash: String,
+    pub creation_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+}
+
+/**
+ * Stores the metadata of an uploaded blob once its ciphertext is in the blob store.
+ * The attachment expires after the default TTL of the chat, like the messages announcing it.
+ */
+pub fn create_item(
+    chat_uuid: Uuid,
+    uuid: Uuid,
+    uploader_uuid: Uuid,
+    size: i64,
+    content_hash: String,
+    mut db: DB,
+) -> Vec<ChatAttachment> {
+    let chat = chats::table
+        .filter(chats::columns::uuid.eq(chat_uuid))
+        .filter(chats::columns::deletion_date.is_null())
+        .select((chats::columns::id, chats::columns::default_ttl))
+        .first::<(i32, Option<i32>)>(&mut db.connection)
+        .optional();
+
+    let (chat_id, default_ttl) = match chat {
+        Ok(Some(chat)) => chat,
+        Ok(None) => return Vec::new(),
+        Err(error) => {
+            sentry::capture_error(&error);
+
+            return Vec::new();
+        }
+    };
+
+    let now = chrono::Utc::now().naive_utc();
+    let new_item = NewChatAttachment {
+        chat_id,
+        uuid,
+        uploader_uuid,
+        size,
+        content_hash,
+        creation_date: now,
+        expiration_date: default_ttl.map(|ttl| now + chrono::Duration::seconds(i64::from(ttl))),
+    };
+
+    let exec = diesel::insert_into(chat_attachments::table)
+        .values(&new_item)
+        .get_results::<ChatAttachment>(&mut db.connection);
+
+    match exec {
+        Ok(items) => items,
+        Err(error) => {
+            sentry::capture_error(&error);
+
+            Vec::new()
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::database::DB;
+use crate::schema::{chat_group_keys, chats};
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, Identifiable, QueryDsl, Queryable, RunQueryDsl};
+use uuid::Uuid;
+
+#[derive(Queryable, Clone, Identifiable)]
+#[diesel(table_name = chat_group_keys)]
+pub struct ChatGroupKey {
+    pub id: i32,
+    pub chat_id: i32,
+    pub user_uuid: Uuid,
+    pub from_user_uuid: Uuid,
+    pub encrypted_key: String,
+    pub iv: String,
+    pub creation_date: NaiveDateTime,
+}
+
+/**
+ * Loads the latest group key wrapped for the user in the chat (if it isn't soft deleted)
+ */
+pub fn fetch(chat_uuid: Uuid, user_uuid: Uuid, mut db: DB) -> Vec<ChatGroupKey> {
+    chat_group_keys::table
+        .filter(
+            chat_group_keys::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .filter(chats::columns::deletion_date.is_null())
+                    .select(chats::columns::id),
+            ),
+        )
+        .filter(chat_group_keys::columns::user_uuid.eq(user_uuid))
+        .load::<ChatGroupKey>(&mut db.connection)
+        .unwrap()
+}
@@ -0,0 +1,79 @@
+use crate::database::DB;
+use crate::schema::{chat_group_keys, chat_members, chats};
+use chrono::NaiveDateTime;
+use diesel::upsert::excluded;
+use diesel::{ExpressionMethods, Insertable, OptionalExtension, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = chat_group_keys)]
+pub struct NewChatGroupKey {
+    pub chat_id: i32,
+    pub user_uuid: Uuid,
+    pub from_user_uuid: Uuid,
+    pub encrypted_key: String,
+    pub iv: String,
+    pub creation_date: NaiveDateTime,
+}
+
+/**
+ * Stores the group key wrapped for the user, replacing the one stored before
+ */
+pub fn create_item(
+    chat_uuid: Uuid,
+    user_uuid: Uuid,
+    from_user_uuid: Uuid,
+    encrypted_key: String,
+    iv: String,
+    creation_date: NaiveDateTime,
+    mut db: DB,
+) -> Option<Uuid> {
+    // Keys are only kept for members, guests just get them live on the socket
+    let chat_id = chats::table
+        .inner_join(chat_members::table)
+        .filter(chats::columns::uuid.eq(chat_uuid))
+        .filter(chat_members::columns::user_uuid.eq(user_uuid))
+        .select(chats::columns::id)
+        .first::<i32>(&mut db.connection)
+        .optional();
+
+    let chat_id = match chat_id {
+        Ok(Some(chat_id)) => chat_id,
+        Ok(None) => return None,
+        Err(error) => {
+            sentry::capture_error(&error);
+
+            return None;
+        }
+    };
+
+    let new_item = NewChatGroupKey {
+        chat_id,
+        user_uuid,
+        from_user_uuid,
+        encrypted_key,
+        iv,
+        creation_date,
+    };
+
+    let exec = diesel::insert_into(chat_group_keys::table)
+        .values(&new_item)
+        .on_conflict((chat_group_keys::columns::chat_id, chat_group_keys::columns::user_uuid))
+        .do_update()
+        .set((
+            chat_group_keys::columns::from_user_uuid.eq(excluded(chat_group_keys::columns::from_user_uuid)),
+            chat_group_keys::columns::encrypted_key.eq(excluded(chat_group_keys::columns::encrypted_key)),
+            chat_group_keys::columns::iv.eq(excluded(chat_group_keys::columns::iv)),
+            chat_group_keys::columns::creation_date.eq(excluded(chat_group_keys::columns::creation_date)),
+        ))
+        .execute(&mut db.connection);
+
+    match exec {
+        Ok(_) => Some(user_uuid),
+        Err(error) => {
+            sentry::capture_error(&error);
+
+            None
+        }
+    }
+}
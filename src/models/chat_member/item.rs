@@ -0,0 +1,173 @@
+use crate::database::DB;
+use crate::models::chat_member::role::Role;
+use crate::schema::{chat_members, chats};
+use chrono::NaiveDateTime;
+use diesel::{BoolExpressionMethods, ExpressionMethods, Identifiable, QueryDsl, Queryable, RunQueryDsl};
+use uuid::Uuid;
+
+#[derive(Queryable, Clone, Identifiable)]
+#[diesel(table_name = chat_members)]
+pub struct ChatMember {
+    pub id: i32,
+    pub chat_id: i32,
+    pub user_uuid: Uuid,
+    pub role: String,
+    pub creation_date: NaiveDateTime,
+    pub modification_date: Option<NaiveDateTime>,
+    pub last_read_message_uuid: Option<Uuid>,
+    /// Sending date of the last read message
+    pub last_read_date: Option<NaiveDateTime>,
+    pub last_delivered_message_uuid: Option<Uuid>,
+    /// Sending date of the last delivered message
+    pub last_delivered_date: Option<NaiveDateTime>,
+}
+
+impl ChatMember {
+    pub fn role(&self) -> Role {
+        // The database constraint only allows known roles, least privileges otherwise
+        Role::from_string(&self.role).unwrap_or(Role::ReadOnly)
+    }
+
+    /// Whether the read pointer got to (or past) the message sent at the given date
+    pub fn has_read(&self, message_sent_at: NaiveDateTime) -> bool {
+        self.last_read_date
+            .is_some_and(|last_read_date| last_read_date >= message_sent_at)
+    }
+}
+
+/**
+ * Loads the membership of the user in the chat (if it isn't soft deleted)
+ */
+pub fn fetch(chat_uuid: Uuid, user_uuid: Uuid, mut db: DB) -> Vec<ChatMember> {
+    chat_members::table
+        .filter(
+            chat_members::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .filter(chats::columns::deletion_date.is_null())
+                    .select(chats::columns::id),
+            ),
+        )
+        .filter(chat_members::columns::user_uuid.eq(user_uuid))
+        .order(chat_members::columns::id.asc())
+        .load::<ChatMember>(&mut db.connection)
+        .unwrap()
+}
+
+pub fn delete(chat_uuid: Uuid, user_uuid: Uuid, mut db: DB) -> Option<Uuid> {
+    let results = chat_members::table
+        .filter(
+            chat_members::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .select(chats::columns::id),
+            ),
+        )
+        .filter(chat_members::columns::user_uuid.eq(user_uuid));
+
+    match diesel::delete(results).execute(&mut db.connection) {
+        Ok(exec) => {
+            if exec > 0 {
+                return Some(user_uuid);
+            }
+
+            None
+        }
+        Err(error) => {
+            // Logging a bit
+            sentry::capture_error(&error);
+
+            None
+        }
+    }
+}
+
+pub fn edit(chat_uuid: Uuid, user_uuid: Uuid, role: Role, mut db: DB) -> Vec<ChatMember> {
+    let results = chat_members::table
+        .filter(
+            chat_members::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .select(chats::columns::id),
+            ),
+        )
+        .filter(chat_members::columns::user_uuid.eq(user_uuid));
+    let exec = diesel::update(results)
+        .set((
+            chat_members::columns::role.eq(role.stringify()),
+            chat_members::columns::modification_date.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(&mut db.connection);
+
+    if let Err(error) = exec {
+        sentry::capture_error(&error);
+    }
+
+    fetch(chat_uuid, user_uuid, db)
+}
+
+/**
+ * Moves the read pointer of the member to the message - but never backwards
+ */
+pub fn mark_read(chat_uuid: Uuid, user_uuid: Uuid, message_uuid: Uuid, message_sent_at: NaiveDateTime, mut db: DB) {
+    let results = chat_members::table
+        .filter(
+            chat_members::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .select(chats::columns::id),
+            ),
+        )
+        .filter(chat_members::columns::user_uuid.eq(user_uuid))
+        .filter(
+            chat_members::columns::last_read_date
+                .is_null()
+                .or(chat_members::columns::last_read_date.lt(message_sent_at)),
+        );
+    let exec = diesel::update(results)
+        .set((
+            chat_members::columns::last_read_message_uuid.eq(message_uuid),
+            chat_members::columns::last_read_date.eq(message_sent_at),
+        ))
+        .execute(&mut db.connection);
+
+    if let Err(error) = exec {
+        sentry::capture_error(&error);
+    }
+}
+
+/**
+ * Moves the delivery pointer of the member to the message - but never backwards
+ */
+pub fn mark_delivered(
+    chat_uuid: Uuid,
+    user_uuid: Uuid,
+    message_uuid: Uuid,
+    message_sent_at: NaiveDateTime,
+    mut db: DB,
+) {
+    let results = chat_members::table
+        .filter(
+            chat_members::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .select(chats::columns::id),
+            ),
+        )
+        .filter(chat_members::columns::user_uuid.eq(user_uuid))
+        .filter(
+            chat_members::columns::last_delivered_date
+                .is_null()
+                .or(chat_members::columns::last_delivered_date.lt(message_sent_at)),
+        );
+    let exec = diesel::update(results)
+        .set((
+            chat_members::columns::last_delivered_message_uuid.eq(message_uuid),
+            chat_members::columns::last_delivered_date.eq(message_sent_at),
+        ))
+        .execute(&mut db.connection);
+
+    if let Err(error) = exec {
+        sentry::capture_error(&error);
+    }
+}
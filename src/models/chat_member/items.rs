@@ -0,0 +1,21 @@
+use crate::database::DB;
+use crate::models::chat_member::item::ChatMember;
+use crate::schema::{chat_members, chats};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+pub fn fetch(chat_uuid: Uuid, mut db: DB) -> Vec<ChatMember> {
+    // Loading it from DB
+    chat_members::table
+        .filter(
+            chat_members::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .filter(chats::columns::deletion_date.is_null())
+                    .select(chats::columns::id),
+            ),
+        )
+        .order(chat_members::columns::id.asc())
+        .load::<ChatMember>(&mut db.connection)
+        .unwrap()
+}
@@ -0,0 +1,55 @@
+use crate::database::DB;
+use crate::models::chat_member::item::{fetch, ChatMember};
+use crate::models::chat_member::role::Role;
+use crate::schema::{chat_members, chats};
+use diesel::{ExpressionMethods, Insertable, OptionalExtension, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = chat_members)]
+pub struct NewChatMember {
+    pub chat_id: i32,
+    pub user_uuid: Uuid,
+    pub role: String,
+}
+
+impl NewChatMember {
+    pub fn new(chat_id: i32, user_uuid: Uuid, role: Role) -> Self {
+        Self {
+            chat_id,
+            user_uuid,
+            role: role.stringify(),
+        }
+    }
+}
+
+pub fn create_item(chat_uuid: Uuid, user_uuid: Uuid, role: Role, mut db: DB) -> Vec<ChatMember> {
+    let chat_id = chats::table
+        .filter(chats::columns::uuid.eq(chat_uuid))
+        .filter(chats::columns::deletion_date.is_null())
+        .select(chats::columns::id)
+        .first::<i32>(&mut db.connection)
+        .optional();
+
+    let chat_id = match chat_id {
+        Ok(Some(chat_id)) => chat_id,
+        Ok(None) => return vec![],
+        Err(error) => {
+            sentry::capture_error(&error);
+
+            return vec![];
+        }
+    };
+
+    let exec = diesel::insert_into(chat_members::table)
+        .values(&NewChatMember::new(chat_id, user_uuid, role))
+        .execute(&mut db.connection);
+
+    if let Err(error) = exec {
+        sentry::capture_error(&error);
+
+        return vec![];
+    }
+
+    fetch(chat_uuid, user_uuid, db)
+}
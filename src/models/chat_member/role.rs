@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/**
+ * `Role` of a member within a chat - ordered from most to least privileged.
+ * Stored as its string representation in `chat_members.role`.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    Owner,
+    Admin,
+    Member,
+    ReadOnly,
+}
+
+impl Role {
+    pub fn stringify(self) -> String {
+        match self {
+            Self::Owner => "Owner".to_string(),
+            Self::Admin => "Admin".to_string(),
+            Self::Member => "Member".to_string(),
+            Self::ReadOnly => "ReadOnly".to_string(),
+        }
+    }
+
+    pub fn from_string(input_string: &str) -> Option<Self> {
+        match input_string {
+            "Owner" => Some(Self::Owner),
+            "Admin" => Some(Self::Admin),
+            "Member" => Some(Self::Member),
+            "ReadOnly" => Some(Self::ReadOnly),
+            _ => None,
+        }
+    }
+
+    const fn rank(self) -> u8 {
+        match self {
+            Self::Owner => 3,
+            Self::Admin => 2,
+            Self::Member => 1,
+            Self::ReadOnly => 0,
+        }
+    }
+
+    /**
+     * Writers may send chat messages, read-only observers may only receive them
+     */
+    pub const fn can_write(self) -> bool {
+        !matches!(self, Self::ReadOnly)
+    }
+
+    /**
+     * Owners and admins manage members, but only those (and roles) ranked below their own
+     */
+    pub const fn can_manage(self, target: Self) -> bool {
+        matches!(self, Self::Owner | Self::Admin) && self.rank() > target.rank()
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", &self.stringify())
+    }
+}
+
+#[cfg(test)]
+mod chat_member_role_tests {
+    use super::Role;
+
+    #[test]
+    fn stringify_and_from_string() {
+        for role in [Role::Owner, Role::Admin, Role::Member, Role::ReadOnly] {
+            assert_eq!(Role::from_string(&role.stringify()), Some(role));
+        }
+
+        assert_eq!(Role::from_string("Guest"), None);
+    }
+
+    #[test]
+    fn can_write() {
+        assert!(Role::Owner.can_write());
+        assert!(Role::Admin.can_write());
+        assert!(Role::Member.can_write());
+        assert!(!Role::ReadOnly.can_write());
+    }
+
+    #[test]
+    fn can_manage() {
+        assert!(Role::Owner.can_manage(Role::Admin));
+        assert!(Role::Owner.can_manage(Role::ReadOnly));
+        assert!(!Role::Owner.can_manage(Role::Owner));
+
+        assert!(Role::Admin.can_manage(Role::Member));
+        assert!(!Role::Admin.can_manage(Role::Admin));
+        assert!(!Role::Admin.can_manage(Role::Owner));
+
+        assert!(!Role::Member.can_manage(Role::ReadOnly));
+        assert!(!Role::ReadOnly.can_manage(Role::ReadOnly));
+    }
+
+    #[test]
+    fn serialize() {
+        assert_eq!(serde_json::to_string(&Role::ReadOnly).unwrap(), r#""ReadOnly""#);
+        assert_eq!(serde_json::from_str::<Role>(r#""Admin""#).unwrap(), Role::Admin);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::database::DB;
+use crate::schema::{chat_messages, chats};
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, Identifiable, QueryDsl, Queryable, RunQueryDsl};
+use uuid::Uuid;
+
+#[derive(Queryable, Clone, Identifiable)]
+#[diesel(table_name = chat_messages)]
+pub struct ChatMessage {
+    pub id: i32,
+    pub chat_id: i32,
+    pub creator_id: Option<i32>,
+    pub uuid: Uuid,
+    pub creation_date: NaiveDateTime,
+    pub cipher: String,
+    pub iv: String,
+    pub sender_uuid: Uuid,
+    pub client_id: Option<String>,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub burn_after_read: bool,
+    pub modification_date: Option<NaiveDateTime>,
+    /// Set once retracted - only a tombstone (without cipher) is left then
+    pub deletion_date: Option<NaiveDateTime>,
+    /// The parent message within the same chat - unset again once the parent got purged
+    pub reply_to: Option<Uuid>,
+}
+
+/**
+ * Loads the message only if it belongs to the chat
+ */
+pub fn fetch(chat_uuid: Uuid, uuid: Uuid, mut db: DB) -> Vec<ChatMessage> {
+    chat_messages::table
+        .filter(chat_messages::columns::uuid.eq(uuid))
+        .filter(
+            chat_messages::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .select(chats::columns::id),
+            ),
+        )
+        .load::<ChatMessage>(&mut db.connection)
+        .unwrap()
+}
+
+/**
+ * Replaces cipher and iv of the message (if it belongs to the chat and isn't retracted)
+ */
+pub fn edit(
+    chat_uuid: Uuid,
+    uuid: Uuid,
+    cipher: String,
+    iv: String,
+    modification_date: NaiveDateTime,
+    mut db: DB,
+) -> Option<Uuid> {
+    let results = chat_messages::table
+        .filter(chat_messages::columns::uuid.eq(uuid))
+        .filter(chat_messages::columns::deletion_date.is_null())
+        .filter(
+            chat_messages::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .select(chats::columns::id),
+            ),
+        );
+
+    match diesel::update(results)
+        .set((
+            chat_messages::columns::cipher.eq(cipher),
+            chat_messages::columns::iv.eq(iv),
+            chat_messages::columns::modification_date.eq(modification_date),
+        ))
+        .execute(&mut db.connection)
+    {
+        Ok(exec) => (exec > 0).then_some(uuid),
+        Err(error) => {
+            sentry::capture_error(&error);
+
+            None
+        }
+    }
+}
+
+/**
+ * Retracts the message: wipes cipher and iv, so only a tombstone keeps its position in the history
+ */
+pub fn tombstone(chat_uuid: Uuid, uuid: Uuid, deletion_date: NaiveDateTime, mut db: DB) -> Option<Uuid> {
+    let results = chat_messages::table
+        .filter(chat_messages::columns::uuid.eq(uuid))
+        .filter(chat_messages::columns::deletion_date.is_null())
+        .filter(
+            chat_messages::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .select(chats::columns::id),
+            ),
+        );
+
+    match diesel::update(results)
+        .set((
+            chat_messages::columns::cipher.eq(""),
+            chat_messages::columns::iv.eq(""),
+            chat_messages::columns::deletion_date.eq(deletion_date),
+        ))
+        .execute(&mut db.connection)
+    {
+        Ok(exec) => (exec > 0).then_some(uuid),
+        Err(error) => {
+            sentry::capture_error(&error);
+
+            None
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::database::DB;
+use crate::models::chat_member::item::ChatMember;
+use crate::models::chat_message::item::ChatMessage;
+use crate::schema::{chat_messages, chats};
+use chrono::NaiveDateTime;
+use diesel::{BoolExpressionMethods, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl};
+use uuid::Uuid;
+
+/**
+ * Position of a message within its chat. Messages are ordered by creation date and id (for equal dates).
+ */
+type Cursor = (NaiveDateTime, i32);
+
+fn fetch_cursor(chat_uuid: Uuid, uuid: Uuid, connection: &mut PgConnection) -> Option<Cursor> {
+    chat_messages::table
+        .inner_join(chats::table)
+        .filter(chats::columns::uuid.eq(chat_uuid))
+        .filter(chat_messages::columns::uuid.eq(uuid))
+        .select((chat_messages::columns::creation_date, chat_messages::columns::id))
+        .first::<Cursor>(connection)
+        .optional()
+        .unwrap_or_else(|error| {
+            sentry::capture_error(&error);
+
+            None
+        })
+}
+
+/**
+ * Loads one page of messages of the chat - always in chronological order.
+ * Without `after` the page ends with the newest message (before the `before` cursor if given), so scrolling back works.
+ * With `after` the page starts right after this message, so clients can catch up after reconnecting.
+ * Returns `None` if one of the cursor messages doesn't belong to the chat.
+ */
+pub fn fetch(
+    chat_uuid: Uuid,
+    before: Option<Uuid>,
+    after: Option<Uuid>,
+    limit: i64,
+    mut db: DB,
+) -> Option<Vec<ChatMessage>> {
+    let before = match before {
+        None => None,
+        Some(uuid) => Some(fetch_cursor(chat_uuid, uuid, &mut db.connection)?),
+    };
+    let after = match after {
+        None => None,
+        Some(uuid) => Some(fetch_cursor(chat_uuid, uuid, &mut db.connection)?),
+    };
+
+    let mut query = chat_messages::table
+        .filter(
+            chat_messages::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .select(chats::columns::id),
+            ),
+        )
+        // Expired messages are gone for clients right away, even if they weren't purged yet
+        .filter(
+            chat_messages::columns::expiration_date
+                .is_null()
+                .or(chat_messages::columns::expiration_date.gt(chrono::Utc::now().naive_utc())),
+        )
+        .limit(limit)
+        .into_boxed();
+
+    if let Some((creation_date, id)) = before {
+        query = query.filter(
+            chat_messages::columns::creation_date
+                .lt(creation_date)
+                .or(chat_messages::columns::creation_date
+                    .eq(creation_date)
+                    .and(chat_messages::columns::id.lt(id))),
+        );
+    }
+
+    if let Some((creation_date, id)) = after {
+        query = query.filter(
+            chat_messages::columns::creation_date
+                .gt(creation_date)
+                .or(chat_messages::columns::creation_date
+                    .eq(creation_date)
+                    .and(chat_messages::columns::id.gt(id))),
+        );
+
+        query = query.order((
+            chat_messages::columns::creation_date.asc(),
+            chat_messages::columns::id.asc(),
+        ));
+    } else {
+        query = query.order((
+            chat_messages::columns::creation_date.desc(),
+            chat_messages::columns::id.desc(),
+        ));
+    }
+
+    let mut items = query.load::<ChatMessage>(&mut db.connection).unwrap();
+
+    if after.is_none() {
+        items.reverse();
+    }
+
+    Some(items)
+}
+
+/**
+ * Loads the messages the member didn't confirm as delivered yet - in chronological order.
+ * Starts right after their delivery pointer (or when they joined), skips their own messages
+ * and anything older than `not_before`.
+ */
+pub fn fetch_undelivered(
+    chat_uuid: Uuid,
+    member: &ChatMember,
+    not_before: NaiveDateTime,
+    limit: i64,
+    mut db: DB,
+) -> Vec<ChatMessage> {
+    // The pointer's message may be purged meanwhile, its date still marks the position then
+    let delivered = member
+        .last_delivered_message_uuid
+        .and_then(|uuid| fetch_cursor(chat_uuid, uuid, &mut db.connection))
+        .or_else(|| member.last_delivered_date.map(|date| (date, i32::MAX)));
+
+    let mut query = chat_messages::table
+        .filter(
+            chat_messages::columns::chat_id.eq_any(
+                chats::table
+                    .filter(chats::columns::uuid.eq(chat_uuid))
+                    .select(chats::columns::id),
+            ),
+        )
+        .filter(chat_messages::columns::sender_uuid.ne(member.user_uuid))
+        // Nothing to deliver for retracted ones
+        .filter(chat_messages::columns::deletion_date.is_null())
+        // Expired messages are gone for clients right away, even if they weren't purged yet
+        .filter(
+            chat_messages::columns::expiration_date
+                .is_null()
+                .or(chat_messages::columns::expiration_date.gt(chrono::Utc::now().naive_utc())),
+        )
+        .filter(chat_messages::columns::creation_date.ge(not_before.max(member.creation_date)))
+        .order((
+            chat_messages::columns::creation_date.asc(),
+            chat_messages::columns::id.asc(),
+        ))
+        .limit(limit)
+        .into_boxed();
+
+    if let Some((creation_date, id)) = delivered {
+        query = query.filter(
+            chat_messages::columns::creation_date
+                .gt(creation_date)
+                .or(chat_messages::columns::creation_date
+                    .eq(creation_date)
+                    .and(chat_messages::columns::id.gt(id))),
+        );
+    }
+
+    query.load::<ChatMessage>(&mut db.connection).unwrap()
+}
@@ -0,0 +1,4 @@
+pub mod item;
+pub mod items;
+pub mod new_item;
+pub mod purge;
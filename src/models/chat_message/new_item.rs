@@ -0,0 +1,145 @@
+use crate::database::DB;
+use crate::models::chat_message::item::ChatMessage;
+use crate::schema::{chat_messages, chats, users};
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, Insertable, OptionalExtension, QueryDsl, RunQueryDsl};
+use log::warn;
+use uuid::Uuid;
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = chat_messages)]
+pub struct NewChatMessage {
+    pub chat_id: i32,
+    pub creator_id: Option<i32>,
+    pub uuid: Uuid,
+    pub cipher: String,
+    pub iv: String,
+    pub sender_uuid: Uuid,
+    pub creation_date: NaiveDateTime,
+    pub client_id: Option<String>,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub burn_after_read: bool,
+    pub reply_to: Option<Uuid>,
+}
+
+/**
+ * Why a chat message was not stored
+ */
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The chat is gone or the database failed (which got reported already)
+    NotStored,
+    /// The reply's parent is no message of the same chat
+    ForeignParent,
+}
+
+/**
+ * Stores the (still encrypted) message for the chat and returns it as stored.
+ * The sender is referenced as registered user as well if there is one with the socket's user uuid.
+ * A message resent with the same client id isn't stored twice - the one stored before is returned instead.
+ * Replies only reference a parent within the same chat.
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn create_item(
+    chat_uuid: Uuid,
+    sender_uuid: Uuid,
+    uuid: Uuid,
+    cipher: String,
+    iv: String,
+    message_sent_at: NaiveDateTime,
+    client_id: Option<String>,
+    expiration_date: Option<NaiveDateTime>,
+    burn_after_read: bool,
+    reply_to: Option<Uuid>,
+    mut db: DB,
+) -> Result<ChatMessage, Error> {
+    let chat_id = chats::table
+        .filter(chats::columns::uuid.eq(chat_uuid))
+        .filter(chats::columns::deletion_date.is_null())
+        .select(chats::columns::id)
+        .first::<i32>(&mut db.connection)
+        .optional();
+
+    let chat_id = match chat_id {
+        Ok(Some(chat_id)) => chat_id,
+        Ok(None) => {
+            warn!("Message {uuid} not stored: chat {chat_uuid} does not exist (anymore)");
+
+            return Err(Error::NotStored);
+        }
+        Err(error) => {
+            sentry::capture_error(&error);
+
+            return Err(Error::NotStored);
+        }
+    };
+
+    // The foreign key only knows the parent exists, not where
+    if let Some(reply_to) = reply_to {
+        let parent = chat_messages::table
+            .filter(chat_messages::columns::uuid.eq(reply_to))
+            .filter(chat_messages::columns::chat_id.eq(chat_id))
+            .select(chat_messages::columns::id)
+            .first::<i32>(&mut db.connection)
+            .optional();
+
+        match parent {
+            Ok(Some(_)) => (),
+            Ok(None) => return Err(Error::ForeignParent),
+            Err(error) => {
+                sentry::capture_error(&error);
+
+                return Err(Error::NotStored);
+            }
+        }
+    }
+
+    let creator_id = users::table
+        .filter(users::columns::uuid.eq(sender_uuid))
+        .select(users::columns::id)
+        .first::<i32>(&mut db.connection)
+        .optional()
+        .unwrap_or_default();
+
+    let new_item = NewChatMessage {
+        chat_id,
+        creator_id,
+        uuid,
+        cipher,
+        iv,
+        sender_uuid,
+        creation_date: message_sent_at,
+        client_id,
+        expiration_date,
+        burn_after_read,
+        reply_to,
+    };
+
+    let inserted = diesel::insert_into(chat_messages::table)
+        .values(&new_item)
+        .on_conflict((
+            chat_messages::columns::chat_id,
+            chat_messages::columns::sender_uuid,
+            chat_messages::columns::client_id,
+        ))
+        .do_nothing()
+        .get_result::<ChatMessage>(&mut db.connection)
+        .optional();
+
+    let stored = match inserted {
+        Ok(Some(item)) => Ok(item),
+        // Only resends conflict - they resolve to what got stored for their client id the first time
+        Ok(None) => chat_messages::table
+            .filter(chat_messages::columns::chat_id.eq(chat_id))
+            .filter(chat_messages::columns::sender_uuid.eq(sender_uuid))
+            .filter(chat_messages::columns::client_id.eq(new_item.client_id))
+            .first::<ChatMessage>(&mut db.connection),
+        Err(error) => Err(error),
+    };
+
+    stored.map_err(|error| {
+        sentry::capture_error(&error);
+
+        Error::NotStored
+    })
+}
@@ -0,0 +1,232 @@
+use crate::database::DB;
+use crate::models::purge_record::new_item::create_item as create_purge_record;
+use crate::models::purge_record::reason::Reason;
+use crate::schema::{chat_attachments, chat_members, chat_messages, chats};
+use chrono::NaiveDateTime;
+use diesel::pg::Pg;
+use diesel::sql_types::Bool;
+use diesel::{
+    BoxableExpression, Connection, ExpressionMethods, NullableExpressionMethods, PgConnection, QueryDsl, RunQueryDsl,
+};
+use uuid::Uuid;
+
+type Filter = Box<dyn BoxableExpression<chat_messages::table, Pg, SqlType = Bool>>;
+type AttachmentFilter = Box<dyn BoxableExpression<chat_attachments::table, Pg, SqlType = Bool>>;
+
+/**
+ * `Purged` - What got deleted of a chat for good, removing the blobs of the attachments is up to the caller
+ */
+#[derive(Debug, Clone)]
+pub struct Purged {
+    pub chat_uuid: Uuid,
+    pub message_uuids: Vec<Uuid>,
+    pub attachment_uuids: Vec<Uuid>,
+}
+
+impl Purged {
+    const fn none(chat_uuid: Uuid) -> Self {
+        Self {
+            chat_uuid,
+            message_uuids: Vec::new(),
+            attachment_uuids: Vec::new(),
+        }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.message_uuids.is_empty() && self.attachment_uuids.is_empty()
+    }
+}
+
+/**
+ * Deletes the matching messages (and attachments, if there is a filter for them) of the chat for good
+ * and records them within the same transaction.
+ */
+fn purge(
+    chat_id: i32,
+    chat_uuid: Uuid,
+    filter: Filter,
+    attachment_filter: Option<AttachmentFilter>,
+    reason: Reason,
+    connection: &mut PgConnection,
+) -> Purged {
+    let exec = connection.transaction::<_, diesel::result::Error, _>(|connection| {
+        let message_uuids = diesel::delete(
+            chat_messages::table
+                .filter(chat_messages::columns::chat_id.eq(chat_id))
+                .filter(filter),
+        )
+        .returning(chat_messages::columns::uuid)
+        .get_results::<Uuid>(connection)?;
+
+        let attachment_uuids = match attachment_filter {
+            Some(attachment_filter) => diesel::delete(
+                chat_attachments::table
+                    .filter(chat_attachments::columns::chat_id.eq(chat_id))
+                    .filter(attachment_filter),
+            )
+            .returning(chat_attachments::columns::uuid)
+            .get_results::<Uuid>(connection)?,
+            None => Vec::new(),
+        };
+
+        let purged = Purged {
+            chat_uuid,
+            message_uuids,
+            attachment_uuids,
+        };
+
+        if !purged.is_empty() {
+            create_purge_record(
+                chat_uuid,
+                reason,
+                &purged.message_uuids,
+                &purged.attachment_uuids,
+                connection,
+            )?;
+        }
+
+        Ok(purged)
+    });
+
+    exec.unwrap_or_else(|error| {
+        sentry::capture_error(&error);
+
+        Purged::none(chat_uuid)
+    })
+}
+
+/**
+ * Deletes all expired messages and attachments - returns what got deleted per chat, so the rooms can be told
+ */
+pub fn delete_expired(mut db: DB) -> Vec<Purged> {
+    let now = chrono::Utc::now().naive_utc();
+    let mut chats = chat_messages::table
+        .inner_join(chats::table)
+        .filter(chat_messages::columns::expiration_date.le(now))
+        .select((chats::columns::id, chats::columns::uuid))
+        .distinct()
+        .load::<(i32, Uuid)>(&mut db.connection)
+        .unwrap();
+
+    chats.extend(
+        chat_attachments::table
+            .inner_join(chats::table)
+            .filter(chat_attachments::columns::expiration_date.le(now))
+            .select((chats::columns::id, chats::columns::uuid))
+            .distinct()
+            .load::<(i32, Uuid)>(&mut db.connection)
+            .unwrap(),
+    );
+    chats.sort_unstable();
+    chats.dedup();
+
+    chats
+        .into_iter()
+        .map(|(chat_id, chat_uuid)| {
+            let filter = Box::new(chat_messages::columns::expiration_date.le(now).assume_not_null());
+            let attachment_filter = Box::new(chat_attachments::columns::expiration_date.le(now).assume_not_null());
+
+            purge(
+                chat_id,
+                chat_uuid,
+                filter,
+                Some(attachment_filter),
+                Reason::Expired,
+                &mut db.connection,
+            )
+        })
+        .filter(|purged| !purged.is_empty())
+        .collect()
+}
+
+/**
+ * Deletes the messages and attachments older than the retention days of their chat - returns what got deleted per chat
+ */
+pub fn delete_retained(mut db: DB) -> Vec<Purged> {
+    let now = chrono::Utc::now().naive_utc();
+    let chats = chats::table
+        .filter(chats::columns::retention_policy.eq("Days"))
+        .select((chats::columns::id, chats::columns::uuid, chats::columns::retention_days))
+        .load::<(i32, Uuid, Option<i32>)>(&mut db.connection)
+        .unwrap();
+
+    chats
+        .into_iter()
+        .filter_map(|(chat_id, chat_uuid, days)| {
+            let retained_since: NaiveDateTime = now - chrono::Duration::days(i64::from(days?));
+            let filter = Box::new(chat_messages::columns::creation_date.lt(retained_since));
+            let attachment_filter = Box::new(chat_attachments::columns::creation_date.lt(retained_since));
+
+            Some(purge(
+                chat_id,
+                chat_uuid,
+                filter,
+                Some(attachment_filter),
+                Reason::Retention,
+                &mut db.connection,
+            ))
+        })
+        .filter(|purged| !purged.is_empty())
+        .collect()
+}
+
+/**
+ * Deletes all messages and attachments of the chat - but only if its retention says so and no member is left
+ */
+pub fn delete_on_empty(chat_uuid: Uuid, mut db: DB) -> Purged {
+    let chat_id = chats::table
+        .filter(chats::columns::uuid.eq(chat_uuid))
+        .filter(chats::columns::retention_policy.eq("DeleteOnEmpty"))
+        .select(chats::columns::id)
+        .load::<i32>(&mut db.connection)
+        .unwrap();
+    let Some(chat_id) = chat_id.first().copied() else {
+        return Purged::none(chat_uuid);
+    };
+
+    let members = chat_members::table
+        .filter(chat_members::columns::chat_id.eq(chat_id))
+        .count()
+        .get_result::<i64>(&mut db.connection)
+        .unwrap();
+
+    if members > 0 {
+        return Purged::none(chat_uuid);
+    }
+
+    let filter = Box::new(chat_messages::columns::chat_id.eq(chat_id));
+    let attachment_filter = Box::new(chat_attachments::columns::chat_id.eq(chat_id));
+
+    purge(
+        chat_id,
+        chat_uuid,
+        filter,
+        Some(attachment_filter),
+        Reason::DeleteOnEmpty,
+        &mut db.connection,
+    )
+}
+
+/**
+ * Deletes the message for good once a burn after read message got read
+ */
+pub fn delete_burned(chat_uuid: Uuid, uuid: Uuid, mut db: DB) -> Option<Uuid> {
+    let chat_id = chats::table
+        .filter(chats::columns::uuid.eq(chat_uuid))
+        .select(chats::columns::id)
+        .load::<i32>(&mut db.connection)
+        .unwrap();
+    let filter = Box::new(chat_messages::columns::uuid.eq(uuid));
+
+    purge(
+        *chat_id.first()?,
+        chat_uuid,
+        filter,
+        None,
+        Reason::BurnedAfterRead,
+        &mut db.connection,
+    )
+    .message_uuids
+    .into_iter()
+    .next()
+}
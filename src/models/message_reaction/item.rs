@@ -0,0 +1,38 @@
+use crate::database::DB;
+use crate::schema::message_reactions;
+use chrono::NaiveDateTime;
+use diesel::{ExpressionMethods, Identifiable, QueryDsl, Queryable, RunQueryDsl};
+use uuid::Uuid;
+
+#[derive(Queryable, Clone, Identifiable)]
+#[diesel(table_name = message_reactions)]
+pub struct MessageReaction {
+    pub id: i32,
+    pub chat_message_id: i32,
+    pub user_uuid: Uuid,
+    pub emoji: String,
+    pub creation_date: NaiveDateTime,
+}
+
+/**
+ * Takes the reaction of the user on the message back - returns the message's id if there was one to remove
+ */
+pub fn delete(chat_message_id: i32, user_uuid: Uuid, emoji: &str, mut db: DB) -> Option<i32> {
+    let exec = diesel::delete(
+        message_reactions::table
+            .filter(message_reactions::columns::chat_message_id.eq(chat_message_id))
+            .filter(message_reactions::columns::user_uuid.eq(user_uuid))
+            .filter(message_reactions::columns::emoji.eq(emoji)),
+    )
+    .execute(&mut db.connection);
+
+    match exec {
+        Ok(0) => None,
+        Ok(_) => Some(chat_message_id),
+        Err(error) => {
+            sentry::capture_error(&error);
+
+            None
+        }
+    }
+}
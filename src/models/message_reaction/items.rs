@@ -0,0 +1,23 @@
+use crate::database::DB;
+use crate::schema::message_reactions;
+use diesel::dsl::count_star;
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+
+/**
+ * Counts the reactions of the messages per emoji - as (message id, emoji, count)
+ */
+pub fn count(chat_message_ids: &[i32], mut db: DB) -> Vec<(i32, String, i64)> {
+    message_reactions::table
+        .filter(message_reactions::columns::chat_message_id.eq_any(chat_message_ids))
+        .group_by((
+            message_reactions::columns::chat_message_id,
+            message_reactions::columns::emoji,
+        ))
+        .select((
+            message_reactions::columns::chat_message_id,
+            message_reactions::columns::emoji,
+            count_star(),
+        ))
+        .load::<(i32, String, i64)>(&mut db.connection)
+        .unwrap()
+}
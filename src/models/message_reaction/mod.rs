@@ -0,0 +1,3 @@
+pub mod item;
+pub mod items;
+pub mod new_item;
@@ -0,0 +1,47 @@
+use crate::database::DB;
+use crate::schema::message_reactions;
+use chrono::NaiveDateTime;
+use diesel::{Insertable, RunQueryDsl};
+use uuid::Uuid;
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = message_reactions)]
+pub struct NewMessageReaction {
+    pub chat_message_id: i32,
+    pub user_uuid: Uuid,
+    pub emoji: String,
+    pub creation_date: NaiveDateTime,
+}
+
+/**
+ * Stores the reaction of the user on the message - returns the message's id unless the user reacted like this already
+ */
+pub fn create_item(
+    chat_message_id: i32,
+    user_uuid: Uuid,
+    emoji: String,
+    creation_date: NaiveDateTime,
+    mut db: DB,
+) -> Option<i32> {
+    let new_item = NewMessageReaction {
+        chat_message_id,
+        user_uuid,
+        emoji,
+        creation_date,
+    };
+
+    let exec = diesel::insert_into(message_reactions::table)
+        .values(&new_item)
+        .on_conflict_do_nothing()
+        .execute(&mut db.connection);
+
+    match exec {
+        Ok(0) => None,
+        Ok(_) => Some(chat_message_id),
+        Err(error) => {
+            sentry::capture_error(&error);
+
+            None
+        }
+    }
+}
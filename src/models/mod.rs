@@ -1 +1,8 @@
+pub mod chat;
+pub mod chat_attachment;
+pub mod chat_group_key;
+pub mod chat_member;
+pub mod chat_message;
+pub mod message_reaction;
+pub mod purge_record;
 pub mod user;
@@ -0,0 +1,2 @@
+pub mod new_item;
+pub mod reason;
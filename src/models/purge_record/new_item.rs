@@ -0,0 +1,37 @@
+use crate::models::purge_record::reason::Reason;
+use crate::schema::purge_records;
+use diesel::{Insertable, PgConnection, QueryResult, RunQueryDsl};
+use uuid::Uuid;
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = purge_records)]
+pub struct NewPurgeRecord {
+    pub chat_uuid: Uuid,
+    pub reason: String,
+    pub message_uuids: Vec<Uuid>,
+    pub message_count: i32,
+    pub attachment_uuids: Vec<Uuid>,
+}
+
+/**
+ * Records what got purged - meant to run within the transaction deleting it, so there is no deletion without proof
+ */
+pub fn create_item(
+    chat_uuid: Uuid,
+    reason: Reason,
+    message_uuids: &[Uuid],
+    attachment_uuids: &[Uuid],
+    connection: &mut PgConnection,
+) -> QueryResult<usize> {
+    let new_item = NewPurgeRecord {
+        chat_uuid,
+        reason: reason.stringify(),
+        message_uuids: message_uuids.to_vec(),
+        message_count: i32::try_from(message_uuids.len()).unwrap_or(i32::MAX),
+        attachment_uuids: attachment_uuids.to_vec(),
+    };
+
+    diesel::insert_into(purge_records::table)
+        .values(&new_item)
+        .execute(connection)
+}
@@ -0,0 +1,31 @@
+use std::fmt;
+
+/**
+ * Why messages (or a whole chat) got deleted for good. Stored as its string representation in `purge_records.reason`.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    Expired,
+    BurnedAfterRead,
+    Retention,
+    DeleteOnEmpty,
+    ChatDeleted,
+}
+
+impl Reason {
+    pub fn stringify(self) -> String {
+        match self {
+            Self::Expired => "Expired".to_string(),
+            Self::BurnedAfterRead => "BurnedAfterRead".to_string(),
+            Self::Retention => "Retention".to_string(),
+            Self::DeleteOnEmpty => "DeleteOnEmpty".to_string(),
+            Self::ChatDeleted => "ChatDeleted".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", &self.stringify())
+    }
+}
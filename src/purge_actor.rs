@@ -0,0 +1,111 @@
+use crate::blob_store::BlobStore;
+use crate::database::DB;
+use crate::helpers::env::get_int;
+use crate::models::chat::purge::delete_soft_deleted;
+use crate::models::chat_message::purge::{delete_expired, delete_retained, Purged};
+use crate::ws_actor::{ChatServer, MessagesPurged};
+use actix::{Actor, Addr, AsyncContext, Context};
+use log::{error, info};
+use std::sync::Arc;
+use std::time::Duration;
+
+/**
+ * Background actor deleting data for good - every `PURGE_INTERVAL` seconds:
+ ** expired messages and attachments and those older than the retention days of their chat - attachment blobs included
+ ** soft deleted chats once `CHAT_DELETION_GRACE_DAYS` passed - with everything in them, attachment blobs included
+ *
+ * Everything purged is recorded in `purge_records`. The chat rooms get told about purged messages,
+ * so connected clients drop their local copies as well.
+ */
+pub struct Purger {
+    chat_server: Addr<ChatServer>,
+    blob_store: Arc<dyn BlobStore>,
+    deletion_grace: chrono::Duration,
+}
+
+impl Purger {
+    pub fn new(chat_server: Addr<ChatServer>, blob_store: Arc<dyn BlobStore>) -> Self {
+        Self {
+            chat_server,
+            blob_store,
+            deletion_grace: chrono::Duration::days(i64::from(get_int("CHAT_DELETION_GRACE_DAYS"))),
+        }
+    }
+
+    fn purge(&self) {
+        let chat_server = self.chat_server.clone();
+        let blob_store = self.blob_store.clone();
+        let deleted_before = chrono::Utc::now().naive_utc() - self.deletion_grace;
+        let deletion = actix_rt::task::spawn_blocking(move || {
+            let mut purged: Vec<Purged> = Vec::new();
+
+            if let Some(db) = DB::acquire() {
+                purged.extend(delete_expired(db));
+            }
+            if let Some(db) = DB::acquire() {
+                purged.extend(delete_retained(db));
+            }
+            for item in &purged {
+                delete_blobs(blob_store.as_ref(), item);
+            }
+            if let Some(db) = DB::acquire() {
+                let chat_uuids = delete_soft_deleted(deleted_before, db);
+
+                if !chat_uuids.is_empty() {
+                    info!("Purged {} deleted chats", chat_uuids.len());
+                }
+
+                for chat_uuid in chat_uuids {
+                    if let Err(error) = blob_store.delete_chat(chat_uuid) {
+                        error!("Blobs of deleted chat {chat_uuid} could not be removed: {error}");
+                    }
+                }
+            }
+
+            purged
+        });
+
+        actix_rt::spawn(async move {
+            let Ok(purged) = deletion.await else {
+                return;
+            };
+
+            for item in purged {
+                info!(
+                    "Purged {} messages and {} attachments of chat {}",
+                    item.message_uuids.len(),
+                    item.attachment_uuids.len(),
+                    item.chat_uuid
+                );
+
+                if !item.message_uuids.is_empty() {
+                    chat_server.do_send(MessagesPurged {
+                        chat_uuid: item.chat_uuid,
+                        message_uuids: item.message_uuids,
+                    });
+                }
+            }
+        });
+    }
+}
+
+/**
+ * Removes the blobs of the purged attachments - their rows are gone already, so failures are only logged
+ */
+pub fn delete_blobs(blob_store: &dyn BlobStore, purged: &Purged) {
+    for uuid in &purged.attachment_uuids {
+        if let Err(error) = blob_store.delete(purged.chat_uuid, *uuid) {
+            error!("Blob of purged attachment {uuid} could not be removed: {error}");
+        }
+    }
+}
+
+impl Actor for Purger {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        let interval = Duration::from_secs(u64::from(get_int("PURGE_INTERVAL")));
+
+        ctx.run_interval(interval, |act, _| act.purge());
+    }
+}
@@ -1,13 +1,61 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    chat_attachments (id) {
+        id -> Int4,
+        chat_id -> Int4,
+        uuid -> Uuid,
+        uploader_uuid -> Uuid,
+        size -> Int8,
+        content_hash -> Varchar,
+        creation_date -> Timestamp,
+        expiration_date -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    chat_group_keys (id) {
+        id -> Int4,
+        chat_id -> Int4,
+        user_uuid -> Uuid,
+        from_user_uuid -> Uuid,
+        encrypted_key -> Text,
+        iv -> Varchar,
+        creation_date -> Timestamp,
+    }
+}
+
+diesel::table! {
+    chat_members (id) {
+        id -> Int4,
+        chat_id -> Int4,
+        user_uuid -> Uuid,
+        role -> Varchar,
+        creation_date -> Timestamp,
+        modification_date -> Nullable<Timestamp>,
+        last_read_message_uuid -> Nullable<Uuid>,
+        last_read_date -> Nullable<Timestamp>,
+        last_delivered_message_uuid -> Nullable<Uuid>,
+        last_delivered_date -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     chat_messages (id) {
         id -> Int4,
         chat_id -> Int4,
-        creator_id -> Int4,
+        creator_id -> Nullable<Int4>,
         uuid -> Uuid,
-        text -> Varchar,
         creation_date -> Timestamp,
+        cipher -> Text,
+        iv -> Varchar,
+        sender_uuid -> Uuid,
+        client_id -> Nullable<Varchar>,
+        expiration_date -> Nullable<Timestamp>,
+        burn_after_read -> Bool,
+        modification_date -> Nullable<Timestamp>,
+        deletion_date -> Nullable<Timestamp>,
+        reply_to -> Nullable<Uuid>,
     }
 }
 
@@ -21,6 +69,35 @@ diesel::table! {
         creation_date -> Timestamp,
         modification_date -> Nullable<Timestamp>,
         deletion_date -> Nullable<Timestamp>,
+        allow_anonymous -> Bool,
+        share_uri_expiration_date -> Nullable<Timestamp>,
+        share_uri_max_uses -> Nullable<Int4>,
+        share_uri_uses -> Int4,
+        default_ttl -> Nullable<Int4>,
+        retention_policy -> Varchar,
+        retention_days -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    message_reactions (id) {
+        id -> Int4,
+        chat_message_id -> Int4,
+        user_uuid -> Uuid,
+        emoji -> Varchar,
+        creation_date -> Timestamp,
+    }
+}
+
+diesel::table! {
+    purge_records (id) {
+        id -> Int4,
+        chat_uuid -> Uuid,
+        reason -> Varchar,
+        message_uuids -> Array<Uuid>,
+        message_count -> Int4,
+        creation_date -> Timestamp,
+        attachment_uuids -> Array<Uuid>,
     }
 }
 
@@ -40,8 +117,21 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(chat_attachments -> chats (chat_id));
+diesel::joinable!(chat_group_keys -> chats (chat_id));
+diesel::joinable!(chat_members -> chats (chat_id));
 diesel::joinable!(chat_messages -> chats (chat_id));
 diesel::joinable!(chat_messages -> users (creator_id));
 diesel::joinable!(chats -> users (creator_id));
+diesel::joinable!(message_reactions -> chat_messages (chat_message_id));
 
-diesel::allow_tables_to_appear_in_same_query!(chat_messages, chats, users,);
+diesel::allow_tables_to_appear_in_same_query!(
+    chat_attachments,
+    chat_group_keys,
+    chat_members,
+    chat_messages,
+    chats,
+    message_reactions,
+    purge_records,
+    users,
+);
@@ -1,5 +1,6 @@
 mod login;
 mod logout;
+mod ticket;
 
 use crate::views::handlers::json_handler::json_error_handler;
 use crate::views::handlers::not_found_handler::not_found;
@@ -10,6 +11,7 @@ pub fn views_factory(app: &mut ServiceConfig) {
         scope("v1/auth")
             .route("login", post().to(login::login))
             .route("logout", get().to(logout::logout))
+            .route("ticket/{uuid}", get().to(ticket::ticket))
             .default_service(route().to(not_found))
             .app_data(JsonConfig::default().error_handler(json_error_handler)),
     );
@@ -0,0 +1,39 @@
+use crate::database::DB;
+use crate::helpers::uuid::parse_uuid_from_request;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::{chat_caller_uuid, WsTicket};
+use crate::models::chat_member::item::fetch as fetch_member;
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/**
+ * Mints a short-lived ticket for opening a WebSocket to the chat given as uuid.
+ * Browsers can't set headers when opening a WebSocket, so the ticket is passed as `?ticket=` query parameter instead.
+ * Only members get one: the socket turns away logged in users without a member row, even in anonymous chats.
+ * Guests of the chat ask for theirs with the guest token they got when redeeming the share link.
+ */
+#[allow(clippy::future_not_send)]
+pub async fn ticket(request: HttpRequest, db: DB) -> HttpResponse {
+    let chat_uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+    let user_uuid = match chat_caller_uuid(&request, chat_uuid) {
+        Err(error) => return error.error_response(),
+        Ok(user_uuid) => user_uuid,
+    };
+
+    if fetch_member(chat_uuid, user_uuid, db).is_empty() {
+        return ApiError::new(ErrorCode::Forbidden, "Not a member of this chat".to_string()).error_response();
+    }
+
+    let raw_ticket = WsTicket::new(user_uuid, chat_uuid).encode();
+    let mut body = HashMap::new();
+    body.insert("ticket", raw_ticket);
+
+    HttpResponse::Created().json(Item::new(Status::Success, "WebSocket ticket created".to_string(), body))
+}
@@ -0,0 +1,153 @@
+use crate::blob_store::BlobStore;
+use crate::database::DB;
+use crate::helpers::env::get_int;
+use crate::helpers::uuid::parse_uuid_from_request;
+use crate::json_serialization::chat_attachment::item::Item as AttachmentItem;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item as ResponseItem;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::JwToken;
+use crate::models::chat_attachment::new_item::create_item;
+use crate::models::chat_member::item::fetch as fetch_member;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Chunks of the body are collected up to this size (in bytes) before they are written to the blob store
+const WRITE_BATCH_SIZE: usize = 1024 * 1024;
+
+/**
+ * Uploads an encrypted blob as raw body (`application/octet-stream`, chunked transfer encoding works as well).
+ * It's streamed into the blob store while hashing it, uploads above `ATTACHMENT_MAX_SIZE` bytes are aborted.
+ * Only writers of the chat may upload, announcing the attachment is up to the client with a `Data::Attachment`.
+ */
+#[allow(clippy::future_not_send)]
+pub async fn create(
+    mut payload: web::Payload,
+    request: HttpRequest,
+    store: web::Data<dyn BlobStore>,
+    db: DB,
+    token: JwToken,
+) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+
+    // The connection goes back to the pool with the lookup - uploads may take a while
+    let Some(member) = fetch_member(uuid, token.user_uuid, db).into_iter().next() else {
+        return ApiError::new(ErrorCode::NotFound, "Error during chat lookup".to_string()).error_response();
+    };
+
+    if !member.role().can_write() {
+        return ApiError::new(
+            ErrorCode::Forbidden,
+            format!("Permission denied: Role {} cannot upload attachments", member.role()),
+        )
+        .error_response();
+    }
+
+    let max_size = usize::try_from(get_int("ATTACHMENT_MAX_SIZE")).unwrap_or(usize::MAX);
+    let store = store.into_inner();
+    let attachment_uuid = Uuid::new_v4();
+    let mut hasher = Sha256::new();
+    let mut size: usize = 0;
+    let mut batch: Vec<u8> = Vec::new();
+
+    while let Some(chunk) = payload.next().await {
+        let Ok(chunk) = chunk else {
+            discard(&store, uuid, attachment_uuid).await;
+
+            return ApiError::new(
+                ErrorCode::BadRequest,
+                "Error during upload: Upload got interrupted".to_string(),
+            )
+            .error_response();
+        };
+
+        size += chunk.len();
+
+        if size > max_size {
+            discard(&store, uuid, attachment_uuid).await;
+
+            return ApiError::new(
+                ErrorCode::PayloadTooLarge,
+                format!("Error during upload: Attachments are limited to {max_size} bytes"),
+            )
+            .error_response();
+        }
+
+        hasher.update(&chunk);
+        batch.extend_from_slice(&chunk);
+
+        if batch.len() >= WRITE_BATCH_SIZE && !write(&store, uuid, attachment_uuid, std::mem::take(&mut batch)).await {
+            return storage_failed(&store, uuid, attachment_uuid).await;
+        }
+    }
+
+    if size == 0 {
+        return ApiError::new(
+            ErrorCode::BadRequest,
+            "Error during upload: Attachment is empty".to_string(),
+        )
+        .error_response();
+    }
+
+    if !batch.is_empty() && !write(&store, uuid, attachment_uuid, batch).await {
+        return storage_failed(&store, uuid, attachment_uuid).await;
+    }
+
+    let Some(db) = DB::acquire() else {
+        return storage_failed(&store, uuid, attachment_uuid).await;
+    };
+
+    let content_hash = hex::encode(hasher.finalize());
+    let item = create_item(
+        uuid,
+        attachment_uuid,
+        token.user_uuid,
+        i64::try_from(size).unwrap_or(i64::MAX),
+        content_hash,
+        db,
+    );
+
+    match item.first() {
+        Some(item) => HttpResponse::Created().json(ResponseItem::new(
+            Status::Success,
+            "Uploaded attachment".to_string(),
+            AttachmentItem::new(item),
+        )),
+        None => storage_failed(&store, uuid, attachment_uuid).await,
+    }
+}
+
+async fn write(store: &Arc<dyn BlobStore>, chat_uuid: Uuid, uuid: Uuid, chunk: Vec<u8>) -> bool {
+    let store = Arc::clone(store);
+
+    matches!(
+        web::block(move || store.append(chat_uuid, uuid, &chunk)).await,
+        Ok(Ok(()))
+    )
+}
+
+/**
+ * Removes what got stored of an upload that failed - there may be nothing at all
+ */
+async fn discard(store: &Arc<dyn BlobStore>, chat_uuid: Uuid, uuid: Uuid) {
+    let store = Arc::clone(store);
+
+    let _ = web::block(move || store.delete(chat_uuid, uuid)).await;
+}
+
+async fn storage_failed(store: &Arc<dyn BlobStore>, chat_uuid: Uuid, uuid: Uuid) -> HttpResponse {
+    discard(store, chat_uuid, uuid).await;
+
+    ApiError::new(
+        ErrorCode::Internal,
+        "Error during upload: Attachment could not be stored".to_string(),
+    )
+    .error_response()
+}
@@ -0,0 +1,59 @@
+use crate::blob_store::BlobStore;
+use crate::database::DB;
+use crate::helpers::uuid::{parse_named_uuid_from_request, parse_uuid_from_request};
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::jwt::chat_caller_uuid;
+use crate::models::chat_attachment::item::fetch;
+use crate::models::chat_member::item::fetch as fetch_member;
+use actix_web::http::header::{ContentType, ETAG};
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+/**
+ * Downloads the still encrypted blob of an attachment - for members of the chat only.
+ * The `ETag` carries the content hash, so clients can verify it before decrypting.
+ */
+#[allow(clippy::future_not_send)]
+pub async fn get(request: HttpRequest, store: web::Data<dyn BlobStore>, db: DB, db2: DB) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+
+    let attachment_uuid: Uuid = match parse_named_uuid_from_request(&request, "attachment_uuid") {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+
+    // Guests download what was sent to them with their guest token
+    let user_uuid = match chat_caller_uuid(&request, uuid) {
+        Err(error) => return error.error_response(),
+        Ok(user_uuid) => user_uuid,
+    };
+
+    if fetch_member(uuid, user_uuid, db).is_empty() {
+        return ApiError::new(ErrorCode::NotFound, "Error during chat lookup".to_string()).error_response();
+    }
+
+    let Some(attachment) = fetch(uuid, attachment_uuid, db2).into_iter().next() else {
+        return ApiError::new(ErrorCode::NotFound, "Error during attachment lookup".to_string()).error_response();
+    };
+
+    let store = store.into_inner();
+
+    match web::block(move || store.open(uuid, attachment_uuid)).await {
+        // Streamed in chunks, attachments may be large
+        Ok(Ok(blob)) => HttpResponse::Ok()
+            .content_type(ContentType::octet_stream())
+            .insert_header((ETAG, format!("\"{}\"", attachment.content_hash)))
+            .no_chunking(u64::try_from(attachment.size).unwrap_or_default())
+            .streaming(ReaderStream::new(blob)),
+        _ => ApiError::new(
+            ErrorCode::Internal,
+            "Error during attachment lookup: Attachment could not be read".to_string(),
+        )
+        .error_response(),
+    }
+}
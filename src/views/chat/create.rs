@@ -0,0 +1,66 @@
+use crate::database::DB;
+use crate::json_serialization::chat::item::Item as ChatItem;
+use crate::json_serialization::chat::new_item::NewItem;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item as ResponseItem;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::JwToken;
+use crate::models::chat::new_item::create_item;
+use actix_web::{web, HttpResponse, ResponseError};
+use sentry::Level;
+
+#[allow(clippy::future_not_send)]
+pub async fn create(new_chat_item: web::Json<NewItem>, db: DB, token: JwToken) -> HttpResponse {
+    let name = new_chat_item.name.trim().to_string();
+
+    if name.is_empty() {
+        return ApiError::new(
+            ErrorCode::UnprocessableEntity,
+            "Name constraint: Must not be empty".to_string(),
+        )
+        .error_response();
+    }
+
+    if new_chat_item.default_ttl.is_some_and(|default_ttl| default_ttl <= 0) {
+        return ApiError::new(
+            ErrorCode::UnprocessableEntity,
+            "TTL constraint: Must be positive".to_string(),
+        )
+        .error_response();
+    }
+
+    if !new_chat_item.retention.is_valid() {
+        return ApiError::new(
+            ErrorCode::UnprocessableEntity,
+            "Retention constraint: Days must be positive".to_string(),
+        )
+        .error_response();
+    }
+
+    // Creating in DB
+    let item = create_item(
+        name,
+        new_chat_item.allow_anonymous,
+        new_chat_item.default_ttl,
+        new_chat_item.retention,
+        token.user_uuid,
+        db,
+    );
+
+    item.first().map_or_else(
+        || {
+            // Logging a bit
+            sentry::capture_message("Storing and lookup of new chat failed!", Level::Error);
+
+            ApiError::new(ErrorCode::Conflict, "Error during chat lookup and creation".to_string()).error_response()
+        },
+        |item| {
+            HttpResponse::Created().json(ResponseItem::new(
+                Status::Success,
+                "Created new chat".to_string(),
+                ChatItem::new(item),
+            ))
+        },
+    )
+}
@@ -0,0 +1,34 @@
+use crate::database::DB;
+use crate::helpers::uuid::parse_uuid_from_request;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::JwToken;
+use crate::models::chat::item::delete as delete_item;
+use crate::ws_actor::{ChatDeleted, ChatServer};
+use actix::Addr;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use uuid::Uuid;
+
+#[allow(clippy::future_not_send)]
+pub async fn delete(request: HttpRequest, srv: web::Data<Addr<ChatServer>>, db: DB, token: JwToken) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+
+    // Soft delete only: the chat stays in DB with its deletion date set
+    delete_item(uuid, token.user_uuid, db).map_or_else(
+        || ApiError::new(ErrorCode::NotFound, "Could not delete".to_string()).error_response(),
+        |uuid| {
+            srv.do_send(ChatDeleted { chat_uuid: uuid });
+
+            HttpResponse::Ok().json(Item::new(
+                Status::Success,
+                "Deleted chat".to_string(),
+                format!("Done with success: {uuid}"),
+            ))
+        },
+    )
+}
@@ -0,0 +1,86 @@
+use crate::database::DB;
+use crate::helpers::uuid::parse_uuid_from_request;
+use crate::json_serialization::chat::edit_item::EditItem;
+use crate::json_serialization::chat::item::Item as ChatItem;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item as ResponseItem;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::JwToken;
+use crate::models::chat::item::edit as edit_item;
+use crate::ws_actor::{ChatChanged, ChatServer};
+use actix::Addr;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use uuid::Uuid;
+
+#[allow(clippy::future_not_send)]
+pub async fn edit(
+    chat_item: web::Json<EditItem>,
+    request: HttpRequest,
+    srv: web::Data<Addr<ChatServer>>,
+    db: DB,
+    token: JwToken,
+) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+
+    let name = chat_item.name.trim().to_string();
+
+    if name.is_empty() {
+        return ApiError::new(
+            ErrorCode::UnprocessableEntity,
+            "Name constraint: Must not be empty".to_string(),
+        )
+        .error_response();
+    }
+
+    if chat_item
+        .default_ttl
+        .flatten()
+        .is_some_and(|default_ttl| default_ttl <= 0)
+    {
+        return ApiError::new(
+            ErrorCode::UnprocessableEntity,
+            "TTL constraint: Must be positive".to_string(),
+        )
+        .error_response();
+    }
+
+    if chat_item.retention.is_some_and(|retention| !retention.is_valid()) {
+        return ApiError::new(
+            ErrorCode::UnprocessableEntity,
+            "Retention constraint: Days must be positive".to_string(),
+        )
+        .error_response();
+    }
+
+    // Editing in DB
+    let item = edit_item(
+        uuid,
+        token.user_uuid,
+        name,
+        chat_item.allow_anonymous,
+        chat_item.default_ttl,
+        chat_item.retention,
+        db,
+    );
+
+    item.first().map_or_else(
+        || ApiError::new(ErrorCode::NotFound, "Error during chat lookup".to_string()).error_response(),
+        |item| {
+            // Open sockets apply the new default TTL right away
+            srv.do_send(ChatChanged {
+                chat_uuid: uuid,
+                default_ttl: item.default_ttl,
+            });
+
+            HttpResponse::Ok().json(ResponseItem::new(
+                Status::Success,
+                "Updated chat".to_string(),
+                ChatItem::new(item),
+            ))
+        },
+    )
+}
@@ -0,0 +1,19 @@
+use crate::database::DB;
+use crate::json_serialization::chat::items::Items;
+use crate::json_serialization::response::item::Item;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::JwToken;
+use crate::models::chat::items::fetch;
+use actix_web::HttpResponse;
+
+#[allow(clippy::future_not_send)]
+pub async fn get(db: DB, token: JwToken) -> HttpResponse {
+    // Loading own chats with default limit: 100
+    let items = fetch(token.user_uuid, None, db);
+
+    HttpResponse::Ok().json(Item::new(
+        Status::Success,
+        format!("Fetched {} chat items", items.len()),
+        Items::new(items),
+    ))
+}
@@ -0,0 +1,33 @@
+use crate::database::DB;
+use crate::helpers::uuid::parse_uuid_from_request;
+use crate::json_serialization::chat::item::Item as ChatItem;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item as ResponseItem;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::JwToken;
+use crate::models::chat::item::fetch_for_member;
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use uuid::Uuid;
+
+#[allow(clippy::future_not_send)]
+pub async fn get_one(request: HttpRequest, db: DB, token: JwToken) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+
+    // Loading it
+    let item = fetch_for_member(uuid, token.user_uuid, db);
+
+    item.first().map_or_else(
+        || ApiError::new(ErrorCode::NotFound, "Error during chat lookup".to_string()).error_response(),
+        |item| {
+            HttpResponse::Ok().json(ResponseItem::new(
+                Status::Success,
+                "Fetched one chat".to_string(),
+                ChatItem::new(item),
+            ))
+        },
+    )
+}
@@ -0,0 +1,52 @@
+use crate::database::DB;
+use crate::helpers::uuid::parse_uuid_from_request;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item as ResponseItem;
+use crate::json_serialization::response::status::Status;
+use crate::json_serialization::web_socket::group_key::GroupKey;
+use crate::jwt::chat_caller_uuid;
+use crate::models::chat_group_key::item::fetch;
+use crate::models::chat_member::item::fetch as fetch_member;
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use uuid::Uuid;
+
+/**
+ * Latest group key wrapped for the requesting user or guest - for late joiners who missed it on the socket
+ */
+#[allow(clippy::future_not_send)]
+pub async fn group_key(request: HttpRequest, db: DB, db2: DB) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+    // Guests joining late fetch theirs with the guest token
+    let user_uuid = match chat_caller_uuid(&request, uuid) {
+        Err(error) => return error.error_response(),
+        Ok(user_uuid) => user_uuid,
+    };
+
+    // Removed members keep their stored key in DB, but must not get it anymore
+    if fetch_member(uuid, user_uuid, db).is_empty() {
+        return ApiError::new(ErrorCode::NotFound, "Error during chat lookup".to_string()).error_response();
+    }
+
+    let item = fetch(uuid, user_uuid, db2);
+
+    item.into_iter().next().map_or_else(
+        || ApiError::new(ErrorCode::NotFound, "Error during group key lookup".to_string()).error_response(),
+        |item| {
+            HttpResponse::Ok().json(ResponseItem::new(
+                Status::Success,
+                "Fetched group key".to_string(),
+                GroupKey::new(
+                    item.encrypted_key,
+                    item.iv,
+                    item.creation_date,
+                    item.user_uuid.to_string(),
+                    item.from_user_uuid.to_string(),
+                ),
+            ))
+        },
+    )
+}
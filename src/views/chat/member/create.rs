@@ -0,0 +1,86 @@
+use crate::database::DB;
+use crate::helpers::uuid::parse_uuid_from_request;
+use crate::json_serialization::chat_member::item::Item as MemberItem;
+use crate::json_serialization::chat_member::new_item::NewItem;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item as ResponseItem;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::JwToken;
+use crate::models::chat_member::item::fetch as fetch_member;
+use crate::models::chat_member::new_item::create_item;
+use crate::models::user::item::fetch as fetch_user;
+use crate::ws_actor::{ChatServer, MemberChanged};
+use actix::Addr;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use uuid::Uuid;
+
+#[allow(clippy::future_not_send)]
+pub async fn create(
+    new_member_item: web::Json<NewItem>,
+    request: HttpRequest,
+    srv: web::Data<Addr<ChatServer>>,
+    db: DB,
+    db2: DB,
+    db3: DB,
+    token: JwToken,
+) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+
+    let user_uuid = match Uuid::parse_str(&new_member_item.user_uuid) {
+        Err(error) => {
+            return ApiError::new(ErrorCode::BadRequest, format!("Uuid has an error: {error}")).error_response()
+        }
+        Ok(valid_uuid) => valid_uuid,
+    };
+
+    let Some(actor) = fetch_member(uuid, token.user_uuid, db).into_iter().next() else {
+        return ApiError::new(ErrorCode::NotFound, "Error during chat lookup".to_string()).error_response();
+    };
+
+    // Nobody can hand out a role equal to or above their own
+    if !actor.role().can_manage(new_member_item.role) {
+        return ApiError::new(
+            ErrorCode::Forbidden,
+            format!(
+                "Permission denied: Role {} cannot add members as {}",
+                actor.role(),
+                new_member_item.role
+            ),
+        )
+        .error_response();
+    }
+
+    if fetch_user(user_uuid, db2).is_empty() {
+        return ApiError::new(ErrorCode::NotFound, "Error during user lookup".to_string()).error_response();
+    }
+
+    // Creating in DB - fails if the user already is a member
+    let item = create_item(uuid, user_uuid, new_member_item.role, db3);
+
+    item.first().map_or_else(
+        || {
+            ApiError::new(
+                ErrorCode::Conflict,
+                "Error during member creation: Already a member".to_string(),
+            )
+            .error_response()
+        },
+        |item| {
+            srv.do_send(MemberChanged {
+                chat_uuid: uuid,
+                user_uuid,
+                role: Some(item.role()),
+            });
+
+            HttpResponse::Created().json(ResponseItem::new(
+                Status::Success,
+                "Added new member".to_string(),
+                MemberItem::new(item),
+            ))
+        },
+    )
+}
@@ -0,0 +1,93 @@
+use crate::blob_store::BlobStore;
+use crate::database::DB;
+use crate::helpers::uuid::{parse_named_uuid_from_request, parse_uuid_from_request};
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::chat_caller_uuid;
+use crate::models::chat_member::item::delete as delete_item;
+use crate::models::chat_member::items::fetch as fetch_members;
+use crate::models::chat_member::role::Role;
+use crate::models::chat_message::purge::delete_on_empty;
+use crate::purge_actor::delete_blobs;
+use crate::ws_actor::{ChatServer, MemberChanged, MessagesPurged};
+use actix::Addr;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use uuid::Uuid;
+
+#[allow(clippy::future_not_send)]
+pub async fn delete(
+    request: HttpRequest,
+    srv: web::Data<Addr<ChatServer>>,
+    store: web::Data<dyn BlobStore>,
+    db: DB,
+    db2: DB,
+    db3: DB,
+) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+    let user_uuid: Uuid = match parse_named_uuid_from_request(&request, "user_uuid") {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+    // Guests can't log in, so they leave with their guest token
+    let caller_uuid = match chat_caller_uuid(&request, uuid) {
+        Err(error) => return error.error_response(),
+        Ok(caller_uuid) => caller_uuid,
+    };
+
+    let members = fetch_members(uuid, db);
+    let (Some(actor), Some(target)) = (
+        members.iter().find(|member| member.user_uuid == caller_uuid),
+        members.iter().find(|member| member.user_uuid == user_uuid),
+    ) else {
+        return ApiError::new(ErrorCode::NotFound, "Could not delete".to_string()).error_response();
+    };
+
+    // Everybody may leave - the owner only as the last one - others may only be removed by higher roles
+    let is_leaving = actor.user_uuid == target.user_uuid && (actor.role() != Role::Owner || members.len() == 1);
+
+    if !is_leaving && !actor.role().can_manage(target.role()) {
+        return ApiError::new(
+            ErrorCode::Forbidden,
+            format!(
+                "Permission denied: Role {} cannot remove {}",
+                actor.role(),
+                target.role()
+            ),
+        )
+        .error_response();
+    }
+
+    delete_item(uuid, user_uuid, db2).map_or_else(
+        || ApiError::new(ErrorCode::NotFound, "Could not delete".to_string()).error_response(),
+        |user_uuid| {
+            srv.do_send(MemberChanged {
+                chat_uuid: uuid,
+                user_uuid,
+                role: None,
+            });
+
+            // The messages and attachments of a chat nobody is left in may have to go with the last member
+            let purged = delete_on_empty(uuid, db3);
+
+            delete_blobs(store.get_ref(), &purged);
+
+            if !purged.message_uuids.is_empty() {
+                srv.do_send(MessagesPurged {
+                    chat_uuid: uuid,
+                    message_uuids: purged.message_uuids,
+                });
+            }
+
+            HttpResponse::Ok().json(Item::new(
+                Status::Success,
+                "Removed member".to_string(),
+                format!("Done with success: {user_uuid}"),
+            ))
+        },
+    )
+}
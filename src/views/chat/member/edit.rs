@@ -0,0 +1,75 @@
+use crate::database::DB;
+use crate::helpers::uuid::{parse_named_uuid_from_request, parse_uuid_from_request};
+use crate::json_serialization::chat_member::edit_item::EditItem;
+use crate::json_serialization::chat_member::item::Item as MemberItem;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item as ResponseItem;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::JwToken;
+use crate::models::chat_member::item::{edit as edit_item, fetch as fetch_member};
+use crate::ws_actor::{ChatServer, MemberChanged};
+use actix::Addr;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use uuid::Uuid;
+
+#[allow(clippy::future_not_send)]
+pub async fn edit(
+    member_item: web::Json<EditItem>,
+    request: HttpRequest,
+    srv: web::Data<Addr<ChatServer>>,
+    db: DB,
+    db2: DB,
+    db3: DB,
+    token: JwToken,
+) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+    let user_uuid: Uuid = match parse_named_uuid_from_request(&request, "user_uuid") {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+
+    let (Some(actor), Some(target)) = (
+        fetch_member(uuid, token.user_uuid, db).into_iter().next(),
+        fetch_member(uuid, user_uuid, db2).into_iter().next(),
+    ) else {
+        return ApiError::new(ErrorCode::NotFound, "Error during member lookup".to_string()).error_response();
+    };
+
+    // Promoting and demoting only works below the own role
+    if !actor.role().can_manage(target.role()) || !actor.role().can_manage(member_item.role) {
+        return ApiError::new(
+            ErrorCode::Forbidden,
+            format!(
+                "Permission denied: Role {} cannot change {} to {}",
+                actor.role(),
+                target.role(),
+                member_item.role
+            ),
+        )
+        .error_response();
+    }
+
+    // Editing in DB
+    let item = edit_item(uuid, user_uuid, member_item.role, db3);
+
+    item.first().map_or_else(
+        || ApiError::new(ErrorCode::NotFound, "Error during member lookup".to_string()).error_response(),
+        |item| {
+            srv.do_send(MemberChanged {
+                chat_uuid: uuid,
+                user_uuid,
+                role: Some(item.role()),
+            });
+
+            HttpResponse::Ok().json(ResponseItem::new(
+                Status::Success,
+                "Updated member".to_string(),
+                MemberItem::new(item),
+            ))
+        },
+    )
+}
@@ -0,0 +1,31 @@
+use crate::database::DB;
+use crate::helpers::uuid::parse_uuid_from_request;
+use crate::json_serialization::chat_member::items::Items;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item as ResponseItem;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::JwToken;
+use crate::models::chat_member::item::fetch as fetch_member;
+use crate::models::chat_member::items::fetch;
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use uuid::Uuid;
+
+#[allow(clippy::future_not_send)]
+pub async fn get(request: HttpRequest, db: DB, db2: DB, token: JwToken) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+
+    // Only members may see who else is in the chat
+    if fetch_member(uuid, token.user_uuid, db).is_empty() {
+        return ApiError::new(ErrorCode::NotFound, "Error during chat lookup".to_string()).error_response();
+    }
+
+    HttpResponse::Ok().json(ResponseItem::new(
+        Status::Success,
+        "Fetched all members".to_string(),
+        Items::new(fetch(uuid, db2)),
+    ))
+}
@@ -0,0 +1,4 @@
+pub mod create;
+pub mod delete;
+pub mod edit;
+pub mod get;
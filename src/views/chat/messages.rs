@@ -0,0 +1,78 @@
+use crate::database::DB;
+use crate::helpers::uuid::parse_uuid_from_request;
+use crate::json_serialization::chat::history::History;
+use crate::json_serialization::chat::message_query::MessageQuery;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item as ResponseItem;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::chat_caller_uuid;
+use crate::models::chat::item::fetch_for_member;
+use crate::models::chat_message::items::fetch;
+use crate::models::message_reaction::items::count as count_reactions;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use uuid::Uuid;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 100;
+
+fn parse_cursor(cursor: Option<&String>) -> Result<Option<Uuid>, HttpResponse> {
+    cursor.map_or(Ok(None), |cursor| {
+        Uuid::parse_str(cursor).map(Some).map_err(|error| {
+            ApiError::new(ErrorCode::BadRequest, format!("Cursor has an error: {error}")).error_response()
+        })
+    })
+}
+
+#[allow(clippy::future_not_send)]
+pub async fn messages(query: web::Query<MessageQuery>, request: HttpRequest, db: DB, db2: DB, db3: DB) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+    // Guests catch up with their guest token
+    let user_uuid = match chat_caller_uuid(&request, uuid) {
+        Err(error) => return error.error_response(),
+        Ok(user_uuid) => user_uuid,
+    };
+
+    let (before, after) = match (parse_cursor(query.before.as_ref()), parse_cursor(query.after.as_ref())) {
+        (Ok(before), Ok(after)) => (before, after),
+        (Err(response), _) | (_, Err(response)) => return response,
+    };
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    // Only members of the chat may read its history
+    if fetch_for_member(uuid, user_uuid, db).is_empty() {
+        return ApiError::new(ErrorCode::NotFound, "Error during chat lookup".to_string()).error_response();
+    }
+
+    // Loading one more than requested to know if there are more messages left
+    let Some(mut items) = fetch(uuid, before, after, limit + 1, db2) else {
+        return ApiError::new(
+            ErrorCode::NotFound,
+            "Error during message lookup: Cursor message not found in chat".to_string(),
+        )
+        .error_response();
+    };
+
+    let has_more = items.len() > usize::try_from(limit).unwrap_or(usize::MAX);
+    if has_more {
+        // Dropping the surplus message on the far side of the page
+        if after.is_some() {
+            items.pop();
+        } else {
+            items.remove(0);
+        }
+    }
+
+    let chat_message_ids: Vec<i32> = items.iter().map(|item| item.id).collect();
+    let reactions = count_reactions(&chat_message_ids, db3);
+
+    HttpResponse::Ok().json(ResponseItem::new(
+        Status::Success,
+        format!("Fetched {} chat messages", items.len()),
+        History::new(items, &reactions, has_more),
+    ))
+}
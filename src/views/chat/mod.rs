@@ -1,13 +1,39 @@
 use crate::views::handlers::json_handler::json_error_handler;
 use crate::views::handlers::not_found_handler::not_found;
-use actix_web::web::{get, route, scope, JsonConfig, ServiceConfig};
+use actix_web::web::{delete, get, patch, post, route, scope, JsonConfig, ServiceConfig};
 
-pub mod new;
+mod attachment;
+mod create;
+mod delete;
+mod edit;
+mod get;
+mod get_one;
+mod group_key;
+mod member;
+mod messages;
+mod new;
+mod share;
 
 pub fn views_factory(app: &mut ServiceConfig) {
     app.service(
         scope("v1/chat")
             .route("uuid", get().to(new::uuid))
+            .route("create", post().to(create::create))
+            .route("get/{uuid}", get().to(get_one::get_one))
+            .route("get", get().to(get::get))
+            .route("edit/{uuid}", patch().to(edit::edit))
+            .route("delete/{uuid}", delete().to(delete::delete))
+            .route("join", post().to(share::redeem::redeem))
+            .route("{uuid}/share", post().to(share::create::create))
+            .route("{uuid}/share", delete().to(share::delete::delete))
+            .route("{uuid}/messages", get().to(messages::messages))
+            .route("{uuid}/group_key", get().to(group_key::group_key))
+            .route("{uuid}/attachments", post().to(attachment::create::create))
+            .route("{uuid}/attachments/{attachment_uuid}", get().to(attachment::get::get))
+            .route("{uuid}/members", get().to(member::get::get))
+            .route("{uuid}/members", post().to(member::create::create))
+            .route("{uuid}/members/{user_uuid}", patch().to(member::edit::edit))
+            .route("{uuid}/members/{user_uuid}", delete().to(member::delete::delete))
             .default_service(route().to(not_found))
             .app_data(JsonConfig::default().error_handler(json_error_handler)),
     );
@@ -0,0 +1,68 @@
+use crate::database::DB;
+use crate::helpers::uuid::parse_uuid_from_request;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item as ResponseItem;
+use crate::json_serialization::response::status::Status;
+use crate::json_serialization::share::item::Item as ShareItem;
+use crate::json_serialization::share::new_item::NewItem;
+use crate::jwt::{JwToken, ShareToken};
+use crate::models::chat::share::create as create_share;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use chrono::Duration;
+use uuid::Uuid;
+
+/**
+ * Mints a share link for the chat. Each call replaces (and thereby revokes) the previous one.
+ */
+#[allow(clippy::future_not_send)]
+pub async fn create(new_share_item: web::Json<NewItem>, request: HttpRequest, db: DB, token: JwToken) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+
+    if new_share_item.max_uses.is_some_and(|max_uses| max_uses < 1) {
+        return ApiError::new(
+            ErrorCode::UnprocessableEntity,
+            "Max uses constraint: Must be at least 1".to_string(),
+        )
+        .error_response();
+    }
+
+    let expiration_date = match new_share_item.lifetime {
+        None => None,
+        Some(lifetime) => match Duration::try_seconds(lifetime).filter(|_| lifetime > 0) {
+            Some(duration) => Some(chrono::Utc::now().naive_utc() + duration),
+            None => {
+                return ApiError::new(
+                    ErrorCode::UnprocessableEntity,
+                    "Lifetime constraint: Must be a positive amount of seconds".to_string(),
+                )
+                .error_response()
+            }
+        },
+    };
+
+    // The share uri is only the revocable part, the token handed out is signed
+    let share_uri = Uuid::new_v4().to_string();
+    let item = create_share(
+        uuid,
+        token.user_uuid,
+        share_uri.clone(),
+        new_share_item.max_uses,
+        expiration_date,
+        db,
+    );
+
+    item.first().map_or_else(
+        || ApiError::new(ErrorCode::NotFound, "Error during chat lookup".to_string()).error_response(),
+        |item| {
+            HttpResponse::Created().json(ResponseItem::new(
+                Status::Success,
+                "Created share link".to_string(),
+                ShareItem::new(ShareToken::new(uuid, share_uri).encode(), item),
+            ))
+        },
+    )
+}
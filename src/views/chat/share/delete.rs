@@ -0,0 +1,30 @@
+use crate::database::DB;
+use crate::helpers::uuid::parse_uuid_from_request;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item;
+use crate::json_serialization::response::status::Status;
+use crate::jwt::JwToken;
+use crate::models::chat::share::revoke;
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use uuid::Uuid;
+
+#[allow(clippy::future_not_send)]
+pub async fn delete(request: HttpRequest, db: DB, token: JwToken) -> HttpResponse {
+    let uuid: Uuid = match parse_uuid_from_request(&request) {
+        Err(response) => return response,
+        Ok(valid_uuid) => valid_uuid,
+    };
+
+    // Already handed out share links stop working immediately
+    revoke(uuid, token.user_uuid, db).map_or_else(
+        || ApiError::new(ErrorCode::NotFound, "Could not revoke".to_string()).error_response(),
+        |uuid| {
+            HttpResponse::Ok().json(Item::new(
+                Status::Success,
+                "Revoked share link".to_string(),
+                format!("Done with success: {uuid}"),
+            ))
+        },
+    )
+}
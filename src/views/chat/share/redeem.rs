@@ -0,0 +1,92 @@
+use crate::database::DB;
+use crate::json_serialization::response::error::Error as ApiError;
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::response::item::Item as ResponseItem;
+use crate::json_serialization::response::status::Status;
+use crate::json_serialization::share::redeem_item::RedeemItem;
+use crate::json_serialization::share::redeemed::Redeemed;
+use crate::jwt::{GuestToken, JwToken, ShareToken, UnauthorizedError, WsTicket};
+use crate::models::chat::item::fetch as fetch_chat;
+use crate::models::chat::share::redeem as redeem_share;
+use crate::models::chat_member::item::fetch as fetch_member;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use uuid::Uuid;
+
+/**
+ * Redeems a share link: logged in users join with their account,
+ * anonymous callers join as guest (if the chat allows it) with a fresh identity, a WebSocket ticket for this chat
+ * and a guest token to get new tickets with, reconnect as the same guest and leave the chat.
+ */
+#[allow(clippy::future_not_send)]
+pub async fn redeem(
+    redeem_item: web::Json<RedeemItem>,
+    request: HttpRequest,
+    db: DB,
+    db2: DB,
+    token: Option<JwToken>,
+) -> HttpResponse {
+    let Some(share_token) = ShareToken::from_token(&redeem_item.share_token) else {
+        return ApiError::new(ErrorCode::NotFound, "Error during share link lookup".to_string()).error_response();
+    };
+    let chat_uuid = share_token.chat_uuid;
+
+    // A broken token must not silently turn the caller into a guest
+    if token.is_none() && request.headers().contains_key("token") {
+        return UnauthorizedError::new("Token cannot be decoded".to_string()).error_response();
+    }
+
+    let (user_uuid, guest_credentials) = if let Some(token) = token {
+        // Members don't use up the share link again
+        if !fetch_member(chat_uuid, token.user_uuid, db).is_empty() {
+            return HttpResponse::Ok().json(ResponseItem::new(
+                Status::Success,
+                "Already a member".to_string(),
+                Redeemed::new(chat_uuid, token.user_uuid, None, None),
+            ));
+        }
+
+        (token.user_uuid, None)
+    } else {
+        if !fetch_chat(chat_uuid, db)
+            .first()
+            .is_some_and(|chat| chat.allow_anonymous)
+        {
+            return ApiError::new(
+                ErrorCode::Unauthorized,
+                "This chat does not allow anonymous guests".to_string(),
+            )
+            .error_response();
+        }
+
+        let guest_uuid = Uuid::new_v4();
+
+        (
+            guest_uuid,
+            Some((
+                WsTicket::new(guest_uuid, chat_uuid).encode(),
+                GuestToken::new(guest_uuid, chat_uuid).encode(),
+            )),
+        )
+    };
+
+    let item = redeem_share(chat_uuid, &share_token.share_uri, user_uuid, db2);
+
+    item.first().map_or_else(
+        || {
+            ApiError::new(
+                ErrorCode::NotFound,
+                "Error during share link lookup: Revoked, expired or used up".to_string(),
+            )
+            .error_response()
+        },
+        |item| {
+            let (ticket, guest_token) = guest_credentials.unzip();
+
+            HttpResponse::Created().json(ResponseItem::new(
+                Status::Success,
+                "Joined chat".to_string(),
+                Redeemed::new(chat_uuid, item.user_uuid, ticket, guest_token),
+            ))
+        },
+    )
+}
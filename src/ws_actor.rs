@@ -1,17 +1,550 @@
+use crate::database::DB;
+use crate::helpers::cipher::is_aes_gcm_iv;
+use crate::helpers::env::get_int;
+use crate::helpers::rate_limit::{Budget, RateLimit, Verdict};
+use crate::json_serialization::response::error_code::ErrorCode;
+use crate::json_serialization::web_socket::ack::Ack;
 use crate::json_serialization::web_socket::chat_message::ChatMessage;
+use crate::json_serialization::web_socket::connection::{Connection, Status as ConnectionStatus};
+use crate::json_serialization::web_socket::delete_message::DeleteMessage;
+use crate::json_serialization::web_socket::edit_message::EditMessage;
+use crate::json_serialization::web_socket::envelope::{decode as decode_envelope, encode as encode_envelope, Encoding};
+use crate::json_serialization::web_socket::error::Error as WsError;
+use crate::json_serialization::web_socket::group_key::GroupKey;
+use crate::json_serialization::web_socket::hello::Hello;
 use crate::json_serialization::web_socket::message::Data::ChatMessage as MessageEnum;
 use crate::json_serialization::web_socket::message::{Data, Message as WsMessage};
 use crate::json_serialization::web_socket::ping::{Knock, Ping};
-use actix::{Actor, Addr, AsyncContext, Context, Handler, Message as ActixMessage, Recipient, StreamHandler};
+use crate::json_serialization::web_socket::purged::Purged;
+use crate::json_serialization::web_socket::reaction::{Action as ReactionAction, Reaction};
+use crate::json_serialization::web_socket::receipt::Kind as ReceiptKind;
+use crate::models::chat_attachment::item::fetch as fetch_attachment;
+use crate::models::chat_group_key::item::fetch as fetch_group_key;
+use crate::models::chat_group_key::new_item::create_item as create_group_key;
+use crate::models::chat_member::item::{fetch as fetch_member, mark_delivered, mark_read, ChatMember};
+use crate::models::chat_member::items::fetch as fetch_members;
+use crate::models::chat_member::role::Role;
+use crate::models::chat_message::item::{
+    edit as edit_chat_message, fetch as fetch_chat_message, tombstone as tombstone_chat_message,
+    ChatMessage as StoredChatMessage,
+};
+use crate::models::chat_message::items::fetch_undelivered;
+use crate::models::chat_message::new_item::{create_item as create_chat_message, Error as StoreError};
+use crate::models::chat_message::purge::delete_burned;
+use crate::models::message_reaction::item::delete as delete_reaction;
+use crate::models::message_reaction::new_item::create_item as create_reaction;
+use actix::{
+    Actor, ActorContext, ActorFutureExt, ActorState, Addr, AsyncContext, Context, Handler, Message as ActixMessage,
+    StreamHandler,
+};
 use actix_web_actors::ws;
+use chrono::NaiveDateTime;
 use log::{error, info, warn};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+const MAX_CLIENT_ID_LENGTH: usize = 64;
+/// Enough for any emoji sequence (in bytes), reactions aren't meant to be messages
+const MAX_REACTION_LENGTH: usize = 32;
+/// Upper bound of messages replayed on reconnect, anything older has to be fetched from the history
+const MAX_REPLAYED_MESSAGES: i64 = 1000;
+
 pub struct MyWs {
     pub chat_uuid: Uuid,
     pub user_uuid: Uuid,
+    /// Whom the rate limit across sessions is kept for
+    pub requester: Requester,
+    /// Role within the chat as resolved during the upgrade - `None` for non-members
+    pub role: Option<Role>,
+    /// Default TTL of the chat as it was during the upgrade - the chat server keeps track of later changes
+    pub default_ttl: Option<i32>,
+    /// Encoding negotiated during the upgrade, everything sent to this session uses it
+    pub encoding: Encoding,
+    /// Protocol version and capabilities the session settled on - `None` until its first frame
+    pub hello: Option<Hello>,
+    /// What the chat server sent before the handshake (like the roster and replay on connect), held back until then
+    pub pending: VecDeque<WsMessage>,
+    /// Most messages held back before the handshake - sessions going beyond are closed
+    pub max_pending: usize,
+    /// Frames the session may send - over the limit ones are rejected, repeat offenders disconnected
+    pub rate_limit: RateLimit,
+    /// Longest cipher (in bytes of its base64) accepted from the client
+    pub max_cipher_length: usize,
     pub users: Addr<ChatServer>,
+    /// Last time the client showed any sign of life
+    pub last_heartbeat: Instant,
+}
+
+impl MyWs {
+    pub fn new(
+        chat_uuid: Uuid,
+        user_uuid: Uuid,
+        requester: Requester,
+        role: Option<Role>,
+        default_ttl: Option<i32>,
+        encoding: Encoding,
+        users: Addr<ChatServer>,
+    ) -> Self {
+        Self {
+            chat_uuid,
+            user_uuid,
+            requester,
+            role,
+            default_ttl,
+            encoding,
+            hello: None,
+            pending: VecDeque::new(),
+            max_pending: usize::try_from(get_int("WS_MAX_PENDING_MESSAGES")).unwrap_or(usize::MAX),
+            rate_limit: RateLimit::new(
+                get_int("WS_RATE_LIMIT_MESSAGES"),
+                get_int("WS_RATE_LIMIT_RECEIPTS"),
+                get_int("WS_RATE_LIMIT_BYTES"),
+                get_int("WS_RATE_LIMIT_STRIKES"),
+                get_ws_rate_limit_strike_window(),
+            ),
+            max_cipher_length: usize::try_from(get_int("WS_MAX_CIPHER_LENGTH")).unwrap_or(usize::MAX),
+            users,
+            last_heartbeat: Instant::now(),
+        }
+    }
+
+    /**
+     * Stamps what the client sent with the authenticated user (and server time) and decides who gets it.
+     * Answers the client directly and returns `None` if there is nothing to hand to the chat server.
+     */
+    fn stamp(&mut self, ctx: &mut <Self as Actor>::Context, data: Data) -> Option<(WsMessage, Recipients)> {
+        match data {
+            MessageEnum(message) => {
+                let chat_message = self.stamp_chat_message(ctx, message)?;
+
+                // The sending session gets an `Ack` instead of its own message
+                Some((WsMessage::new(Data::ChatMessage(chat_message)), Recipients::Others))
+            }
+            Data::Connection(mut connection) => {
+                // Same as for messages: nobody can announce presence on behalf of others
+                connection.user_id = self.user_uuid.to_string();
+
+                Some((WsMessage::new(Data::Connection(connection)), Recipients::Others))
+            }
+            Data::Ping(_ping) => {
+                // Only the pinging client is interested in the answer
+                self.send(ctx, &WsMessage::new(Data::Ping(Ping::new(Knock::Pong))));
+
+                None
+            }
+            Data::Receipt(mut receipt) => {
+                // Receipts are always given by the authenticated user
+                receipt.user_id = self.user_uuid.to_string();
+
+                // Narrowed down to the sender of the message once the server looked it up
+                Some((WsMessage::new(Data::Receipt(receipt)), Recipients::Others))
+            }
+            Data::Typing(mut typing) => {
+                typing.user_id = self.user_uuid.to_string();
+
+                Some((WsMessage::new(Data::Typing(typing)), Recipients::Others))
+            }
+            Data::GroupKey(group_key) => {
+                if group_key.from_user_id != self.user_uuid.to_string() {
+                    warn!(
+                        "Rejecting group key of client {} in chat {}: sent as {}",
+                        self.user_uuid, self.chat_uuid, group_key.from_user_id
+                    );
+
+                    self.send_error(
+                        ctx,
+                        ErrorCode::Forbidden,
+                        "Group keys can only be sent as yourself",
+                        None,
+                    );
+
+                    return None;
+                }
+
+                let Ok(for_user_uuid) = Uuid::parse_str(&group_key.for_user_id) else {
+                    self.send_error(ctx, ErrorCode::BadRequest, "Invalid for_user_id", None);
+
+                    return None;
+                };
+
+                // The key is wrapped for exactly one user, nobody else needs to see it
+                Some((
+                    WsMessage::new(Data::GroupKey(group_key)),
+                    Recipients::Users(vec![for_user_uuid]),
+                ))
+            }
+            Data::EditMessage(mut edit_message) => {
+                // Only the author may edit, so it's always the authenticated user - the server decides about the time
+                edit_message.user_id = self.user_uuid.to_string();
+                edit_message.modification_date = Some(chrono::Utc::now().naive_utc());
+
+                // The sender learns about the edit succeeding by getting it relayed as well
+                Some((WsMessage::new(Data::EditMessage(edit_message)), Recipients::Room))
+            }
+            Data::DeleteMessage(mut delete_message) => {
+                delete_message.user_id = self.user_uuid.to_string();
+                delete_message.deletion_date = Some(chrono::Utc::now().naive_utc());
+
+                Some((WsMessage::new(Data::DeleteMessage(delete_message)), Recipients::Room))
+            }
+            Data::Reaction(mut reaction) => {
+                if reaction.emoji.is_empty() || reaction.emoji.len() > MAX_REACTION_LENGTH {
+                    self.send_error(
+                        ctx,
+                        ErrorCode::BadRequest,
+                        &format!("emoji must have 1 to {MAX_REACTION_LENGTH} bytes"),
+                        Some(reaction.message_uuid),
+                    );
+
+                    return None;
+                }
+
+                reaction.user_id = self.user_uuid.to_string();
+
+                Some((WsMessage::new(Data::Reaction(reaction)), Recipients::Room))
+            }
+            Data::Attachment(mut attachment) => {
+                if Uuid::parse_str(&attachment.blob_uuid).is_err() {
+                    self.send_error(ctx, ErrorCode::BadRequest, "Invalid blob_uuid", None);
+
+                    return None;
+                }
+
+                attachment.user_id = self.user_uuid.to_string();
+
+                Some((WsMessage::new(Data::Attachment(attachment)), Recipients::Room))
+            }
+            Data::Hello(hello) => {
+                if self.handshake(ctx, &hello) {
+                    // Tells the client which version and capabilities the server settled on
+                    self.send(ctx, &WsMessage::new(Data::Hello(hello.negotiate())));
+                    self.deliver_pending(ctx);
+                }
+
+                None
+            }
+            Data::Ack(_) | Data::Error(_) | Data::Purged(_) => {
+                self.send_error(ctx, ErrorCode::BadRequest, "Only sent by the server", None);
+
+                None
+            }
+        }
+    }
+
+    /**
+     * Validates a chat message of the client and stamps it with uuid, sender and timestamp of the server.
+     * Answers the client with an error and returns `None` if it's invalid.
+     */
+    fn stamp_chat_message(&self, ctx: &mut <Self as Actor>::Context, message: ChatMessage) -> Option<ChatMessage> {
+        if message
+            .client_id
+            .as_ref()
+            .is_some_and(|client_id| client_id.is_empty() || client_id.len() > MAX_CLIENT_ID_LENGTH)
+        {
+            self.send_error(
+                ctx,
+                ErrorCode::BadRequest,
+                "client_id must have 1 to 64 characters",
+                None,
+            );
+
+            return None;
+        }
+
+        if message.ttl.is_some_and(|ttl| ttl <= 0) {
+            self.send_error(ctx, ErrorCode::BadRequest, "ttl must be positive", None);
+
+            return None;
+        }
+
+        if message
+            .reply_to
+            .as_deref()
+            .is_some_and(|reply_to| Uuid::parse_str(reply_to).is_err())
+        {
+            self.send_error(ctx, ErrorCode::BadRequest, "Invalid reply_to", message.client_id);
+
+            return None;
+        }
+
+        // The sender is always the authenticated user, never what the client claims
+        let mut chat_message = ChatMessage::new(
+            Uuid::new_v4().to_string(),
+            self.user_uuid.to_string(),
+            message.cipher,
+            message.iv,
+            chrono::Utc::now().naive_utc(),
+        );
+        chat_message.client_id = message.client_id;
+        chat_message.ttl = message.ttl;
+        chat_message.burn_after_read = message.burn_after_read;
+        chat_message.reply_to = message.reply_to;
+
+        Some(chat_message)
+    }
+
+    /**
+     * Answers the client with an error - unless it's a legacy client, which doesn't know error frames
+     */
+    fn send_error(
+        &self,
+        ctx: &mut <Self as Actor>::Context,
+        code: ErrorCode,
+        message: &str,
+        correlation_id: Option<String>,
+    ) {
+        let frame = error_frame(code, message, correlation_id);
+
+        if self.hello.as_ref().is_none_or(|hello| hello.understands(&frame.data)) {
+            self.send(ctx, &frame);
+        }
+    }
+
+    /**
+     * Writes a message to the client - as text frame or binary envelope, depending on the negotiated encoding
+     */
+    fn send(&self, ctx: &mut <Self as Actor>::Context, message: &WsMessage) {
+        match self.encoding {
+            Encoding::Json => ctx.text(serde_json::to_string(message).unwrap()),
+            Encoding::MessagePack => ctx.binary(encode_envelope(message)),
+        }
+    }
+
+    /**
+     * Hands a parsed message of the client (whichever frame it came in) to the chat server
+     */
+    fn receive(&mut self, ctx: &mut <Self as Actor>::Context, message: WsMessage, size: usize) {
+        if !self.within_rate_limit(ctx, Some(&message.data), size) {
+            return;
+        }
+
+        // Sessions that don't open with a `Hello` speak the legacy protocol
+        if self.hello.is_none() && !matches!(message.data, Data::Hello(_)) {
+            if !self.handshake(ctx, &Hello::legacy()) {
+                return;
+            }
+
+            self.deliver_pending(ctx);
+        }
+
+        if !self.within_payload_limits(ctx, &message.data) {
+            return;
+        }
+
+        let Some((response_message, recipients)) = self.stamp(ctx, message.data) else {
+            return;
+        };
+
+        self.users.do_send(BroadcastMessage {
+            chat_uuid: self.chat_uuid,
+            user_uuid: self.user_uuid,
+            requester: self.requester,
+            addr: ctx.address(),
+            recipients,
+            message: response_message.clone(),
+            size,
+        });
+        // Todo: remove this verbosity
+        info!(
+            "Did send following message to chat room {:?}: {:?}",
+            self.chat_uuid, response_message
+        );
+    }
+
+    /**
+     * Checks the size of ciphers and the shape of IVs and public keys before anything gets stamped or relayed
+     */
+    fn within_payload_limits(&self, ctx: &mut <Self as Actor>::Context, data: &Data) -> bool {
+        let (cipher, iv, correlation_id) = match data {
+            Data::ChatMessage(chat_message) => (&chat_message.cipher, &chat_message.iv, chat_message.client_id.clone()),
+            Data::EditMessage(edit_message) => (
+                &edit_message.cipher,
+                &edit_message.iv,
+                Some(edit_message.message_uuid.clone()),
+            ),
+            Data::Attachment(attachment) => (&attachment.cipher, &attachment.iv, Some(attachment.blob_uuid.clone())),
+            Data::GroupKey(group_key) => (&group_key.encrypted_key, &group_key.iv, None),
+            Data::Connection(connection) if !connection.public_key.is_valid() => {
+                self.send_error(ctx, ErrorCode::BadRequest, "Invalid public_key", None);
+
+                return false;
+            }
+            _ => return true,
+        };
+
+        if cipher.len() > self.max_cipher_length {
+            self.send_error(
+                ctx,
+                ErrorCode::PayloadTooLarge,
+                &format!("cipher must not exceed {} bytes", self.max_cipher_length),
+                correlation_id,
+            );
+
+            return false;
+        }
+
+        if !is_aes_gcm_iv(iv) {
+            self.send_error(
+                ctx,
+                ErrorCode::BadRequest,
+                "iv must be a base64 encoded 12 byte AES-GCM IV",
+                correlation_id,
+            );
+
+            return false;
+        }
+
+        true
+    }
+
+    /**
+     * Checks a frame against the rate limit of the session, rejecting it (or closing the session) when over it.
+     * Frames that couldn't be parsed are taken from the messages budget.
+     */
+    fn within_rate_limit(&mut self, ctx: &mut <Self as Actor>::Context, data: Option<&Data>, size: usize) -> bool {
+        match self
+            .rate_limit
+            .check(size, data.map_or(Budget::Messages, budget), Instant::now())
+        {
+            Verdict::Allowed => return true,
+            Verdict::Limited => self.send_error(ctx, ErrorCode::TooManyRequests, "Rate limit exceeded", None),
+            Verdict::Banned => {
+                self.send_error(ctx, ErrorCode::TooManyRequests, "Rate limit exceeded repeatedly", None);
+                self.close(ctx, "Rate limit exceeded repeatedly".to_string());
+            }
+        }
+
+        false
+    }
+
+    /**
+     * Settles the protocol of the session, closing it if the negotiated version is below `WS_MIN_PROTOCOL_VERSION`.
+     * Returns whether the session may go on.
+     */
+    fn handshake(&mut self, ctx: &mut <Self as Actor>::Context, requested: &Hello) -> bool {
+        if self.hello.is_some() {
+            self.send_error(ctx, ErrorCode::BadRequest, "Hello has to be the first message", None);
+
+            return false;
+        }
+
+        let accepted = requested.negotiate();
+        let min_version = get_ws_min_protocol_version();
+
+        if accepted.version < min_version {
+            warn!(
+                "Client {} in chat {} speaks protocol version {}, closing socket",
+                self.user_uuid, self.chat_uuid, accepted.version
+            );
+
+            self.send_error(
+                ctx,
+                ErrorCode::UpgradeRequired,
+                &format!("Protocol version {min_version} or newer required"),
+                None,
+            );
+            self.close(ctx, "Unsupported protocol version".to_string());
+
+            return false;
+        }
+
+        self.hello = Some(accepted);
+
+        true
+    }
+
+    /**
+     * Sends what got held back until the handshake - left out are message types the session didn't opt into
+     */
+    fn deliver_pending(&mut self, ctx: &mut <Self as Actor>::Context) {
+        for message in std::mem::take(&mut self.pending) {
+            if self
+                .hello
+                .as_ref()
+                .is_some_and(|hello| hello.understands(&message.data))
+            {
+                self.send(ctx, &message);
+            }
+        }
+    }
+
+    /**
+     * Closes the socket from the server side with a policy violation
+     */
+    fn close(&self, ctx: &mut <Self as Actor>::Context, reason: String) {
+        info!(
+            "Closing socket of client {} in chat {}: {}",
+            self.user_uuid, self.chat_uuid, reason
+        );
+
+        ctx.close(Some(ws::CloseReason {
+            code: ws::CloseCode::Policy,
+            description: Some(reason),
+        }));
+        ctx.stop();
+    }
+
+    /**
+     * Pings the client every heartbeat interval and stops the session once it stayed silent for longer than the timeout.
+     * Stopping emits the usual `Disconnect`, so the chat room gets cleaned up as well.
+     */
+    fn heartbeat(ctx: &mut <Self as Actor>::Context) {
+        let client_timeout = get_ws_client_timeout();
+
+        ctx.run_interval(get_ws_heartbeat_interval(), move |act, ctx| {
+            if Instant::now().duration_since(act.last_heartbeat) > client_timeout {
+                warn!(
+                    "Client {} in chat {} timed out, closing socket",
+                    act.user_uuid, act.chat_uuid
+                );
+
+                ctx.stop();
+
+                return;
+            }
+
+            ctx.ping(b"");
+        });
+    }
+}
+
+/**
+* Builds the `Error` frame for the offending session, referencing the client id of the message (if there is one)
+*/
+fn error_frame(code: ErrorCode, message: &str, correlation_id: Option<String>) -> WsMessage {
+    WsMessage::new(Data::Error(WsError::new(code, message.to_string(), correlation_id)))
+}
+
+/**
+* Receipts and pings are answers to what the client got, so they don't use up the budget of its messages
+*/
+const fn budget(data: &Data) -> Budget {
+    match data {
+        Data::Receipt(_) | Data::Ping(_) => Budget::Receipts,
+        _ => Budget::Messages,
+    }
+}
+
+fn get_ws_rate_limit_strike_window() -> Duration {
+    Duration::from_secs(u64::from(get_int("WS_RATE_LIMIT_STRIKE_WINDOW")))
+}
+
+fn get_ws_heartbeat_interval() -> Duration {
+    Duration::from_secs(u64::from(get_int("WS_HEARTBEAT_INTERVAL")))
+}
+
+fn get_ws_client_timeout() -> Duration {
+    Duration::from_secs(u64::from(get_int("WS_CLIENT_TIMEOUT")))
+}
+
+fn get_ws_handshake_timeout() -> Duration {
+    Duration::from_secs(u64::from(get_int("WS_HANDSHAKE_TIMEOUT")))
+}
+
+fn get_ws_min_protocol_version() -> u32 {
+    get_int("WS_MIN_PROTOCOL_VERSION")
 }
 
 impl Actor for MyWs {
@@ -20,19 +553,31 @@ impl Actor for MyWs {
     fn started(&mut self, ctx: &mut Self::Context) {
         info!("Client {} connected to socket {}", self.user_uuid, self.chat_uuid);
 
+        Self::heartbeat(ctx);
+
+        // Even legacy clients open with a frame (settling the handshake) - silent ones only pile up what's held back
+        ctx.run_later(get_ws_handshake_timeout(), |act, ctx| {
+            if act.hello.is_none() {
+                act.close(ctx, "No handshake in time".to_string());
+            }
+        });
+
         self.users.do_send(Connect {
             chat_uuid: self.chat_uuid,
             user_uuid: self.user_uuid,
-            addr: ctx.address().recipient(),
+            role: self.role,
+            default_ttl: self.default_ttl,
+            addr: ctx.address(),
         });
     }
 
-    fn stopped(&mut self, _: &mut Self::Context) {
+    fn stopped(&mut self, ctx: &mut Self::Context) {
         info!("Client {} disconnected to socket {}", self.user_uuid, self.chat_uuid);
 
         self.users.do_send(Disconnect {
             chat_uuid: self.chat_uuid,
             user_uuid: self.user_uuid,
+            addr: ctx.address(),
         });
     }
 }
@@ -50,10 +595,35 @@ impl Handler<WsMessage> for MyWs {
     type Result = ();
 
     fn handle(&mut self, msg: WsMessage, ctx: &mut Self::Context) -> Self::Result {
-        // For debugging purposes, you can log all the messages here!
-        let response = serde_json::to_string(&msg).unwrap();
+        // Message types the session didn't opt into are left out, before the handshake it's unknown which these are
+        match &self.hello {
+            None if self.pending.len() >= self.max_pending => {
+                // Messages it missed are replayed on reconnect
+                if ctx.state() == ActorState::Running {
+                    self.close(ctx, "Too many messages held back before the handshake".to_string());
+                }
+            }
+            None => self.pending.push_back(msg),
+            Some(hello) if hello.understands(&msg.data) => self.send(ctx, &msg),
+            Some(_) => (),
+        }
+    }
+}
 
-        ctx.text(response);
+/**
+* Closes the socket from the server side, e.g. when the user isn't (or no longer) a member of the chat
+*/
+#[derive(ActixMessage)]
+#[rtype(result = "()")]
+pub struct CloseSession {
+    pub reason: String,
+}
+
+impl Handler<CloseSession> for MyWs {
+    type Result = ();
+
+    fn handle(&mut self, msg: CloseSession, ctx: &mut Self::Context) -> Self::Result {
+        self.close(ctx, msg.reason);
     }
 }
 
@@ -63,46 +633,36 @@ impl Handler<WsMessage> for MyWs {
 */
 impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for MyWs {
     fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        // Every frame counts as sign of life, not only pongs
+        if msg.is_ok() {
+            self.last_heartbeat = Instant::now();
+        }
+
         match msg {
-            Ok(ws::Message::Text(text)) => {
-                if let Ok(chat_message) = serde_json::from_str::<WsMessage>(&text) {
-                    let response_message = match chat_message.data {
-                        MessageEnum(message) => {
-                            let uuid = Uuid::new_v4();
-
-                            WsMessage::new(Data::ChatMessage(ChatMessage::new(
-                                uuid.to_string(),
-                                message.user_id,
-                                message.cipher,
-                                message.iv,
-                                chrono::Utc::now().naive_utc(),
-                            )))
-                        }
-                        Data::Connection(connection) => WsMessage::new(Data::Connection(connection)),
-                        Data::Ping(_ping) => WsMessage::new(Data::Ping(Ping::new(Knock::Pong))),
-                        Data::GroupKey(group_key) => {
-                            // Todo: currently only relaying the message
-                            WsMessage::new(Data::GroupKey(group_key))
-                        }
-                    };
-
-                    // Todo: storing the message in the database
-
-                    self.users.do_send(BroadcastMessage {
-                        chat_uuid: self.chat_uuid,
-                        message: response_message.clone(),
-                    });
-                    // Todo: remove this verbosity
-                    info!(
-                        "Did send following message to chat room {:?}: {:?}",
-                        self.chat_uuid, response_message
-                    );
-                } else {
+            Ok(ws::Message::Text(text)) => match WsMessage::from_json(&text) {
+                Ok(chat_message) => self.receive(ctx, chat_message, text.len()),
+                Err(_) if !self.within_rate_limit(ctx, None, text.len()) => (),
+                Err(reason) => {
                     warn!("WebSocket error during parsing of message: {text}");
+
+                    self.send_error(ctx, ErrorCode::BadRequest, &reason, None);
                 }
-            }
+            },
             Ok(ws::Message::Ping(msg)) => ctx.pong(&msg),
-            Ok(ws::Message::Binary(bin)) => ctx.binary(bin),
+            Ok(ws::Message::Binary(bin)) => match decode_envelope(&bin) {
+                Ok(chat_message) => self.receive(ctx, chat_message, bin.len()),
+                Err(_) if !self.within_rate_limit(ctx, None, bin.len()) => (),
+                Err(reason) => {
+                    warn!("WebSocket error during decoding of a binary frame: {reason}");
+
+                    self.send_error(ctx, ErrorCode::BadRequest, &reason, None);
+                }
+            },
+            Err(ws::ProtocolError::Overflow) => {
+                // The rest of the frame can't be skipped, so the session is done
+                self.send_error(ctx, ErrorCode::PayloadTooLarge, "Frame exceeds the maximum size", None);
+                self.close(ctx, "Frame exceeds the maximum size".to_string());
+            }
             Err(error) => error!("WebSocket error: {error}"),
             _ => (),
         }
@@ -114,7 +674,9 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for MyWs {
 struct Connect {
     chat_uuid: Uuid,
     user_uuid: Uuid,
-    addr: Recipient<WsMessage>,
+    role: Option<Role>,
+    default_ttl: Option<i32>,
+    addr: Addr<MyWs>,
 }
 
 #[derive(ActixMessage)]
@@ -122,41 +684,806 @@ struct Connect {
 struct Disconnect {
     chat_uuid: Uuid,
     user_uuid: Uuid,
+    addr: Addr<MyWs>,
 }
 
 #[derive(ActixMessage)]
 #[rtype(result = "()")]
 struct BroadcastMessage {
     chat_uuid: Uuid, // Chat room UUID to identify which chat room to broadcast to
+    user_uuid: Uuid, // Sender of the message
+    requester: Requester,
+    addr: Addr<MyWs>, // Sending session, the one to tell about errors
+    recipients: Recipients,
     message: WsMessage,
+    size: usize, // Size of the frame it came in, counted against the rate limit of the sender
+}
+
+/**
+* Whom the rate limit of a session is kept for across all their sessions: the user - or the address of anonymous
+* visitors, as they get a new identity with every connection
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Requester {
+    User(Uuid),
+    Address(IpAddr),
+}
+
+/**
+* Asks whether the requester is banned for exceeding the rate limit repeatedly - they must not open new sockets then
+*/
+#[derive(ActixMessage)]
+#[rtype(result = "bool")]
+pub struct IsBanned {
+    pub requester: Requester,
+}
+
+/**
+* Who within the chat room a message gets delivered to
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Recipients {
+    /// Every session, the sending one included
+    Room,
+    /// Every session but the sending one - other sessions of the same user included
+    Others,
+    /// All sessions of the given users only
+    Users(Vec<Uuid>),
+}
+
+impl Recipients {
+    fn includes(&self, user_uuid: Uuid, is_sender: bool) -> bool {
+        match self {
+            Self::Room => true,
+            Self::Others => !is_sender,
+            Self::Users(user_uuids) => user_uuids.contains(&user_uuid),
+        }
+    }
+}
+
+/**
+* Sent whenever a membership changed outside of the socket (REST): `None` as role if the member got removed
+*/
+#[derive(ActixMessage)]
+#[rtype(result = "()")]
+pub struct MemberChanged {
+    pub chat_uuid: Uuid,
+    pub user_uuid: Uuid,
+    pub role: Option<Role>,
+}
+
+/**
+* Sent whenever the settings of a chat changed outside of the socket (REST)
+*/
+#[derive(ActixMessage)]
+#[rtype(result = "()")]
+pub struct ChatChanged {
+    pub chat_uuid: Uuid,
+    pub default_ttl: Option<i32>,
+}
+
+/**
+* Sent once a chat got (soft) deleted - every socket of it gets closed
+*/
+#[derive(ActixMessage)]
+#[rtype(result = "()")]
+pub struct ChatDeleted {
+    pub chat_uuid: Uuid,
+}
+
+/**
+* Sent by the `Purger` once messages of the chat expired and got deleted
+*/
+#[derive(ActixMessage)]
+#[rtype(result = "()")]
+pub struct MessagesPurged {
+    pub chat_uuid: Uuid,
+    pub message_uuids: Vec<Uuid>,
+}
+
+/**
+* One connected socket within a chat room
+*/
+struct Session {
+    user_uuid: Uuid,
+    role: Role,
+    addr: Addr<MyWs>,
+    /// Latest presence the client announced - `None` until it sent its first `Connection`
+    connection: Option<Connection>,
+    /// When the last `Typing` of this session got relayed - to throttle them
+    last_typing: Option<Instant>,
 }
 
 pub struct ChatServer {
-    chat_rooms: HashMap<Uuid, HashSet<(Uuid, Recipient<WsMessage>)>>,
+    chat_rooms: HashMap<Uuid, Vec<Session>>,
+    typing_throttle: Duration,
+    /// How far back messages are replayed to members reconnecting
+    offline_retention: Duration,
+    /// Default TTL (in seconds) of the chat rooms having one
+    default_ttls: HashMap<Uuid, i32>,
+    /// Rate limits across all sessions of a requester - kept until there is nothing left to remember, not only
+    /// while they are connected, so reconnecting doesn't lift limits or bans
+    rate_limits: HashMap<Requester, RateLimit>,
+    /// What users start with
+    user_rate_limit: RateLimit,
 }
 
 impl ChatServer {
     pub fn new() -> Self {
         Self {
             chat_rooms: HashMap::new(),
+            typing_throttle: Duration::from_millis(u64::from(get_int("WS_TYPING_THROTTLE"))),
+            offline_retention: Duration::from_secs(u64::from(get_int("WS_OFFLINE_RETENTION"))),
+            default_ttls: HashMap::new(),
+            rate_limits: HashMap::new(),
+            user_rate_limit: RateLimit::new(
+                get_int("WS_USER_RATE_LIMIT_MESSAGES"),
+                get_int("WS_USER_RATE_LIMIT_RECEIPTS"),
+                get_int("WS_USER_RATE_LIMIT_BYTES"),
+                get_int("WS_RATE_LIMIT_STRIKES"),
+                get_ws_rate_limit_strike_window(),
+            ),
+        }
+    }
+
+    /**
+     * Checks a message against the rate limit of its sender, rejecting it (or closing the session) when over it
+     */
+    fn within_rate_limit(&mut self, msg: &BroadcastMessage) -> bool {
+        let rate_limit = self
+            .rate_limits
+            .entry(msg.requester)
+            .or_insert_with(|| self.user_rate_limit.clone());
+
+        match rate_limit.check(msg.size, budget(&msg.message.data), Instant::now()) {
+            Verdict::Allowed => return true,
+            Verdict::Limited => {
+                msg.addr
+                    .do_send(error_frame(ErrorCode::TooManyRequests, "Rate limit exceeded", None));
+            }
+            Verdict::Banned => {
+                msg.addr.do_send(error_frame(
+                    ErrorCode::TooManyRequests,
+                    "Rate limit exceeded repeatedly",
+                    None,
+                ));
+                msg.addr.do_send(CloseSession {
+                    reason: "Rate limit exceeded repeatedly".to_string(),
+                });
+            }
+        }
+
+        false
+    }
+
+    /**
+     * Tells everyone in the room to delete the messages (expired or burned) - those offline rely on the expiration date
+     */
+    fn purge(&self, chat_uuid: Uuid, message_uuids: &[Uuid]) {
+        let purged = Purged::new(message_uuids.iter().map(Uuid::to_string).collect());
+
+        self.deliver(
+            chat_uuid,
+            None,
+            &Recipients::Room,
+            &WsMessage::new(Data::Purged(purged)),
+        );
+    }
+
+    /**
+     * Delivers the message to the sessions of the room matching the recipients - the one and only way out
+     */
+    fn deliver(&self, chat_uuid: Uuid, sender: Option<&Addr<MyWs>>, recipients: &Recipients, message: &WsMessage) {
+        if let Some(sessions) = self.chat_rooms.get(&chat_uuid) {
+            for session in sessions
+                .iter()
+                .filter(|session| recipients.includes(session.user_uuid, sender == Some(&session.addr)))
+            {
+                session.addr.do_send(message.clone());
+            }
+        }
+    }
+
+    /**
+     * Stores a chat message of a writer, then relays it to the others and acknowledges it to the sender.
+     * A resent message (same client id) is only acknowledged again - with what got stored the first time.
+     */
+    fn relay_chat_message(&self, ctx: &mut Context<Self>, msg: BroadcastMessage, chat_message: &ChatMessage) {
+        // Only writers may send chat messages, read-only observers are just listening
+        if !self
+            .chat_rooms
+            .get(&msg.chat_uuid)
+            .is_some_and(|sessions| can_write(sessions, msg.user_uuid))
+        {
+            warn!(
+                "Dropping message of client {} in chat {}: no write permission",
+                msg.user_uuid, msg.chat_uuid
+            );
+
+            msg.addr.do_send(error_frame(
+                ErrorCode::Forbidden,
+                "No write permission",
+                chat_message.client_id.clone(),
+            ));
+
+            return;
+        }
+
+        let Some(message_sent_at) = chat_message.message_sent_at else {
+            return;
+        };
+        // The sender's TTL wins over the chat's default one
+        let expiration_date = chat_message
+            .ttl
+            .or_else(|| self.default_ttls.get(&msg.chat_uuid).copied())
+            .map(|ttl| message_sent_at + chrono::Duration::seconds(i64::from(ttl)));
+
+        let mut stored_message = chat_message.clone();
+        stored_message.ttl = None;
+        stored_message.expiration_date = expiration_date;
+
+        store_message(ctx, stored_message, msg);
+    }
+
+    /**
+     * Relays the stored message to the others - without the idempotency key - acknowledging it to the sender.
+     * Resends only get the acknowledgement of the message stored before.
+     */
+    fn publish_chat_message(&self, msg: &BroadcastMessage, message: ChatMessage, stored: &StoredChatMessage) {
+        let mut ack = Ack::new(stored.client_id.clone(), stored.uuid.to_string(), stored.creation_date);
+        ack.expiration_date = stored.expiration_date;
+
+        if message.uuid.as_ref() != Some(&stored.uuid.to_string()) {
+            info!(
+                "Client {} resent message {} in chat {}",
+                msg.user_uuid,
+                stored.client_id.as_deref().unwrap_or_default(),
+                msg.chat_uuid
+            );
+
+            msg.addr.do_send(WsMessage::new(Data::Ack(ack)));
+
+            return;
+        }
+
+        // The idempotency key is nobody else's business
+        let mut relayed_message = message;
+        relayed_message.client_id = None;
+        // Everyone gets the timestamp as stored (and acknowledged)
+        relayed_message.message_sent_at = Some(stored.creation_date);
+
+        self.deliver(
+            msg.chat_uuid,
+            Some(&msg.addr),
+            &msg.recipients,
+            &WsMessage::new(Data::ChatMessage(relayed_message)),
+        );
+        msg.addr.do_send(WsMessage::new(Data::Ack(ack)));
+    }
+
+    /**
+     * Relays a typing indicator to everyone but the typing session - neither stored nor echoed.
+     * Repeated "still typing" are dropped within the throttle, stopping always gets through.
+     */
+    fn relay_typing(&mut self, msg: &BroadcastMessage, is_typing: bool) {
+        let Some(sessions) = self.chat_rooms.get_mut(&msg.chat_uuid) else {
+            return;
+        };
+
+        if !can_write(sessions, msg.user_uuid) {
+            msg.addr
+                .do_send(error_frame(ErrorCode::Forbidden, "No write permission", None));
+
+            return;
+        }
+
+        let Some(session) = sessions.iter_mut().find(|session| session.addr == msg.addr) else {
+            return;
+        };
+
+        if is_typing {
+            if session
+                .last_typing
+                .is_some_and(|last_typing| last_typing.elapsed() < self.typing_throttle)
+            {
+                return;
+            }
+
+            session.last_typing = Some(Instant::now());
+        } else {
+            session.last_typing = None;
+        }
+
+        self.deliver(msg.chat_uuid, Some(&msg.addr), &msg.recipients, &msg.message);
+    }
+
+    /**
+     * Announces the user as gone - but only once the last of their sessions in the room left
+     */
+    fn announce_leave(&self, chat_uuid: Uuid, user_uuid: Uuid, connection: Option<Connection>) {
+        let still_present = self
+            .chat_rooms
+            .get(&chat_uuid)
+            .is_some_and(|sessions| sessions.iter().any(|session| session.user_uuid == user_uuid));
+
+        if let (false, Some(mut connection)) = (still_present, connection) {
+            connection.status = ConnectionStatus::Disconnected;
+
+            self.deliver(
+                chat_uuid,
+                None,
+                &Recipients::Room,
+                &WsMessage::new(Data::Connection(connection)),
+            );
         }
     }
 }
 
 impl Actor for ChatServer {
     type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        // Rate limits with full budgets and forgiven strikes are the same as new ones
+        ctx.run_interval(get_ws_rate_limit_strike_window(), |act, _| {
+            let now = Instant::now();
+
+            act.rate_limits.retain(|_, rate_limit| !rate_limit.is_settled(now));
+        });
+    }
+}
+
+fn can_write(sessions: &[Session], user_uuid: Uuid) -> bool {
+    sessions
+        .iter()
+        .any(|session| session.user_uuid == user_uuid && session.role.can_write())
+}
+
+/**
+* Persists the latest group key of the recipient, so they can still fetch it when joining later
+*/
+fn store_group_key(chat_uuid: Uuid, user_uuid: Uuid, from_user_uuid: Uuid, group_key: &GroupKey) {
+    let encrypted_key = group_key.encrypted_key.clone();
+    let iv = group_key.iv.clone();
+    let creation_date = group_key.creation_date;
+
+    actix_rt::task::spawn_blocking(move || {
+        if let Some(db) = DB::acquire() {
+            create_group_key(
+                chat_uuid,
+                user_uuid,
+                from_user_uuid,
+                encrypted_key,
+                iv,
+                creation_date,
+                db,
+            );
+        }
+    });
+}
+
+/**
+* Persists the (encrypted) message in a blocking task and publishes it once stored.
+* Nothing is relayed before the message is in the database - a sender not getting an `Ack` may safely resend it.
+* Replies to messages of other chats are rejected, so the corrected one may be sent with the same client id again.
+*/
+fn store_message(ctx: &mut Context<ChatServer>, message: ChatMessage, msg: BroadcastMessage) {
+    let (chat_uuid, user_uuid) = (msg.chat_uuid, msg.user_uuid);
+    let client_id = message.client_id.clone();
+    let (expiration_date, burn_after_read) = (message.expiration_date, message.burn_after_read);
+    let reply_to = message.reply_to.as_deref().and_then(|uuid| Uuid::parse_str(uuid).ok());
+    let (Some(Ok(uuid)), Some(message_sent_at)) =
+        (message.uuid.as_deref().map(Uuid::parse_str), message.message_sent_at)
+    else {
+        warn!("Message of {user_uuid} in chat {chat_uuid} not stored: uuid or timestamp missing");
+
+        return;
+    };
+    let cipher = message.cipher.clone();
+    let iv = message.iv.clone();
+
+    let stored = actix_rt::task::spawn_blocking(move || {
+        create_chat_message(
+            chat_uuid,
+            user_uuid,
+            uuid,
+            cipher,
+            iv,
+            message_sent_at,
+            client_id,
+            expiration_date,
+            burn_after_read,
+            reply_to,
+            DB::acquire().ok_or(StoreError::NotStored)?,
+        )
+    });
+
+    ctx.spawn(
+        actix::fut::wrap_future::<_, ChatServer>(stored).map(move |result, act, _| match result {
+            Ok(Ok(stored)) => act.publish_chat_message(&msg, message, &stored),
+            Ok(Err(StoreError::ForeignParent)) => {
+                warn!(
+                    "Dropping reply of client {user_uuid} in chat {chat_uuid}: {} is no message of the chat",
+                    message.reply_to.unwrap_or_default()
+                );
+
+                msg.addr.do_send(error_frame(
+                    ErrorCode::BadRequest,
+                    "reply_to is no message of this chat",
+                    message.client_id,
+                ));
+            }
+            _ => msg.addr.do_send(error_frame(
+                ErrorCode::Internal,
+                "Message could not be stored",
+                message.client_id,
+            )),
+        }),
+    );
+}
+
+/**
+* Looks up the sender of the message in the background and hands them the receipt (if they are connected).
+* Receipts move the recipient's delivery pointer (and `Read` ones the read pointer as well), so the sender can still
+* see it later on and reconnecting doesn't replay the message again.
+* Burn after read messages are deleted with the last missing `Read` receipt of the other members and purged from
+* the room.
+*/
+fn route_receipt(ctx: &mut Context<ChatServer>, message_uuid: Uuid, kind: ReceiptKind, msg: BroadcastMessage) {
+    let (chat_uuid, user_uuid) = (msg.chat_uuid, msg.user_uuid);
+    let lookup = actix_rt::task::spawn_blocking(move || {
+        let message = fetch_chat_message(chat_uuid, message_uuid, DB::acquire()?)
+            .into_iter()
+            .next()?;
+
+        // Only others' messages count, the own ones are never replayed anyway
+        if message.sender_uuid != user_uuid {
+            // Reading a message implies it got delivered
+            mark_delivered(
+                chat_uuid,
+                user_uuid,
+                message_uuid,
+                message.creation_date,
+                DB::acquire()?,
+            );
+
+            if kind == ReceiptKind::Read {
+                mark_read(
+                    chat_uuid,
+                    user_uuid,
+                    message_uuid,
+                    message.creation_date,
+                    DB::acquire()?,
+                );
+
+                if message.burn_after_read
+                    && read_by_all(
+                        &fetch_members(chat_uuid, DB::acquire()?),
+                        message.sender_uuid,
+                        message.creation_date,
+                    )
+                {
+                    let burned = delete_burned(chat_uuid, message_uuid, DB::acquire()?).is_some();
+
+                    return Some((message.sender_uuid, burned));
+                }
+            }
+        }
+
+        Some((message.sender_uuid, false))
+    });
+
+    ctx.spawn(
+        actix::fut::wrap_future::<_, ChatServer>(lookup).map(move |result, act, _| match result {
+            // Nobody needs to be told about reading their own messages
+            Ok(Some((sender_uuid, _))) if sender_uuid == user_uuid => (),
+            Ok(Some((sender_uuid, burned))) => {
+                act.deliver(
+                    chat_uuid,
+                    Some(&msg.addr),
+                    &Recipients::Users(vec![sender_uuid]),
+                    &msg.message,
+                );
+
+                if burned {
+                    act.purge(chat_uuid, &[message_uuid]);
+                }
+            }
+            _ => msg.addr.do_send(error_frame(
+                ErrorCode::NotFound,
+                "Unknown message",
+                Some(message_uuid.to_string()),
+            )),
+        }),
+    );
+}
+
+/**
+* Whether every current member but the sender has read the message sent at the given date
+*/
+fn read_by_all(members: &[ChatMember], sender_uuid: Uuid, message_sent_at: NaiveDateTime) -> bool {
+    members
+        .iter()
+        .filter(|member| member.user_uuid != sender_uuid)
+        .all(|member| member.has_read(message_sent_at))
+}
+
+/**
+* Relays the announcement of an attachment once it's clear it was uploaded to this chat by the sender,
+* exactly with the announced size and content hash.
+*/
+fn route_attachment(ctx: &mut Context<ChatServer>, sessions: &[Session], msg: BroadcastMessage) {
+    // Sharing files is writing, the upload checks it as well - but roles may have changed since
+    if !can_write(sessions, msg.user_uuid) {
+        msg.addr
+            .do_send(error_frame(ErrorCode::Forbidden, "No write permission", None));
+
+        return;
+    }
+
+    let Data::Attachment(attachment) = msg.message.data.clone() else {
+        return;
+    };
+    // The session validated the uuid already
+    let Ok(attachment_uuid) = Uuid::parse_str(&attachment.blob_uuid) else {
+        return;
+    };
+    let chat_uuid = msg.chat_uuid;
+    let stored = actix_rt::task::spawn_blocking(move || {
+        DB::acquire().map(|db| fetch_attachment(chat_uuid, attachment_uuid, db))
+    });
+
+    ctx.spawn(
+        actix::fut::wrap_future::<_, ChatServer>(stored).map(move |result, act, _| {
+            let (code, message) = match result.ok().flatten().map(|items| items.into_iter().next()) {
+                Some(None) => (ErrorCode::NotFound, "Unknown attachment"),
+                Some(Some(item)) if item.uploader_uuid != msg.user_uuid => {
+                    (ErrorCode::Forbidden, "Only the uploader may announce an attachment")
+                }
+                Some(Some(item))
+                    if item.size != attachment.size
+                        || !item.content_hash.eq_ignore_ascii_case(&attachment.content_hash) =>
+                {
+                    (ErrorCode::BadRequest, "size or content_hash don't match the upload")
+                }
+                Some(Some(_)) => {
+                    act.deliver(chat_uuid, Some(&msg.addr), &msg.recipients, &msg.message);
+
+                    return;
+                }
+                None => (ErrorCode::Internal, "Attachment could not be checked"),
+            };
+
+            msg.addr
+                .do_send(error_frame(code, message, Some(attachment_uuid.to_string())));
+        }),
+    );
+}
+
+/**
+* Adds or removes the reaction in the background and relays it once it's done.
+* Only chat members may react (guests may not) and only once per emoji on each message.
+*/
+fn route_reaction(ctx: &mut Context<ChatServer>, message_uuid: Uuid, msg: BroadcastMessage) {
+    let (chat_uuid, user_uuid) = (msg.chat_uuid, msg.user_uuid);
+    let Data::Reaction(reaction) = msg.message.data.clone() else {
+        return;
+    };
+    let stored = actix_rt::task::spawn_blocking(move || {
+        if fetch_member(chat_uuid, user_uuid, DB::acquire().ok_or(ErrorCode::Internal)?).is_empty() {
+            return Err(ErrorCode::Forbidden);
+        }
+
+        let message = fetch_chat_message(chat_uuid, message_uuid, DB::acquire().ok_or(ErrorCode::Internal)?)
+            .into_iter()
+            .find(|message| message.deletion_date.is_none())
+            .ok_or(ErrorCode::NotFound)?;
+        let db = DB::acquire().ok_or(ErrorCode::Internal)?;
+
+        match reaction.action {
+            ReactionAction::Add => create_reaction(
+                message.id,
+                user_uuid,
+                reaction.emoji,
+                chrono::Utc::now().naive_utc(),
+                db,
+            )
+            .map(|_| ())
+            .ok_or(ErrorCode::Conflict),
+            ReactionAction::Remove => delete_reaction(message.id, user_uuid, &reaction.emoji, db)
+                .map(|_| ())
+                .ok_or(ErrorCode::NotFound),
+        }
+    });
+
+    ctx.spawn(
+        actix::fut::wrap_future::<_, ChatServer>(stored).map(move |result, act, _| {
+            let code = match result {
+                Ok(Ok(())) => {
+                    act.deliver(chat_uuid, Some(&msg.addr), &msg.recipients, &msg.message);
+
+                    return;
+                }
+                Ok(Err(code)) => code,
+                Err(_) => ErrorCode::Internal,
+            };
+            let message = match code {
+                ErrorCode::Forbidden => "Only chat members may react",
+                ErrorCode::NotFound => "Unknown message or reaction",
+                ErrorCode::Conflict => "Already reacted like this",
+                _ => "Reaction could not be stored",
+            };
+
+            msg.addr
+                .do_send(error_frame(code, message, Some(message_uuid.to_string())));
+        }),
+    );
+}
+
+/**
+* Applies an `EditMessage` or `DeleteMessage` to the stored message in the background and relays it once it's done.
+* Only authors may edit their messages, retracting them is allowed for those able to moderate the chat as well.
+*/
+fn route_amendment(ctx: &mut Context<ChatServer>, sessions: &[Session], message_uuid: Uuid, msg: BroadcastMessage) {
+    // Editing is writing, retracting is always possible for authors - and for owners and admins as moderation
+    if matches!(msg.message.data, Data::EditMessage(_)) && !can_write(sessions, msg.user_uuid) {
+        msg.addr.do_send(error_frame(
+            ErrorCode::Forbidden,
+            "No write permission",
+            Some(message_uuid.to_string()),
+        ));
+
+        return;
+    }
+
+    let can_moderate = sessions
+        .iter()
+        .any(|session| session.user_uuid == msg.user_uuid && matches!(session.role, Role::Owner | Role::Admin));
+    let (chat_uuid, user_uuid) = (msg.chat_uuid, msg.user_uuid);
+    let data = msg.message.data.clone();
+    let amendment = actix_rt::task::spawn_blocking(move || {
+        let message = fetch_chat_message(chat_uuid, message_uuid, DB::acquire().ok_or(ErrorCode::Internal)?)
+            .into_iter()
+            .find(|message| message.deletion_date.is_none())
+            .ok_or(ErrorCode::NotFound)?;
+        let is_author = message.sender_uuid == user_uuid;
+
+        let amended = match data {
+            Data::EditMessage(edit_message) if is_author => edit_chat_message(
+                chat_uuid,
+                message_uuid,
+                edit_message.cipher,
+                edit_message.iv,
+                edit_message
+                    .modification_date
+                    .unwrap_or_else(|| chrono::Utc::now().naive_utc()),
+                DB::acquire().ok_or(ErrorCode::Internal)?,
+            ),
+            Data::DeleteMessage(delete_message) if is_author || can_moderate => tombstone_chat_message(
+                chat_uuid,
+                message_uuid,
+                delete_message
+                    .deletion_date
+                    .unwrap_or_else(|| chrono::Utc::now().naive_utc()),
+                DB::acquire().ok_or(ErrorCode::Internal)?,
+            ),
+            _ => return Err(ErrorCode::Forbidden),
+        };
+
+        amended.map(|_| ()).ok_or(ErrorCode::NotFound)
+    });
+
+    ctx.spawn(
+        actix::fut::wrap_future::<_, ChatServer>(amendment).map(move |result, act, _| {
+            let code = match result {
+                Ok(Ok(())) => {
+                    act.deliver(chat_uuid, Some(&msg.addr), &msg.recipients, &msg.message);
+
+                    return;
+                }
+                Ok(Err(code)) => code,
+                Err(_) => ErrorCode::Internal,
+            };
+            let message = match code {
+                ErrorCode::NotFound => "Unknown message",
+                ErrorCode::Forbidden => "Not allowed to change this message",
+                _ => "Message could not be changed",
+            };
+
+            msg.addr
+                .do_send(error_frame(code, message, Some(message_uuid.to_string())));
+        }),
+    );
+}
+
+/**
+* Replays what the member missed while being offline (within the retention window): their current group key first,
+* as nothing can be decrypted without it, then the messages after their delivery pointer in order.
+* Clients confirm them with `Delivered` receipts like any other message.
+*/
+fn replay_undelivered(chat_uuid: Uuid, user_uuid: Uuid, addr: Addr<MyWs>, retention: Duration) {
+    let not_before = chrono::Utc::now().naive_utc() - retention;
+    let lookup = actix_rt::task::spawn_blocking(move || {
+        let member = fetch_member(chat_uuid, user_uuid, DB::acquire()?).into_iter().next()?;
+        let group_keys = fetch_group_key(chat_uuid, user_uuid, DB::acquire()?);
+        let messages = fetch_undelivered(chat_uuid, &member, not_before, MAX_REPLAYED_MESSAGES, DB::acquire()?);
+
+        Some((group_keys, messages))
+    });
+
+    actix_rt::spawn(async move {
+        let Ok(Some((group_keys, messages))) = lookup.await else {
+            return;
+        };
+
+        for group_key in group_keys {
+            addr.do_send(WsMessage::new(Data::GroupKey(GroupKey::new(
+                group_key.encrypted_key,
+                group_key.iv,
+                group_key.creation_date,
+                group_key.user_uuid.to_string(),
+                group_key.from_user_uuid.to_string(),
+            ))));
+        }
+
+        info!(
+            "Replaying {} messages to client {user_uuid} in chat {chat_uuid}",
+            messages.len()
+        );
+
+        for message in messages {
+            addr.do_send(WsMessage::new(Data::ChatMessage(ChatMessage::from_model(message))));
+        }
+    });
 }
 
 impl Handler<Connect> for ChatServer {
     type Result = ();
 
     fn handle(&mut self, msg: Connect, _: &mut Self::Context) {
-        info!("Adding client {} to chat {}", msg.user_uuid, msg.chat_uuid);
+        // Only members may join the room
+        let Some(role) = msg.role else {
+            warn!(
+                "Rejecting client {} for chat {}: not a member",
+                msg.user_uuid, msg.chat_uuid
+            );
+
+            msg.addr.do_send(CloseSession {
+                reason: "Not a member of this chat".to_string(),
+            });
+
+            return;
+        };
+
+        info!("Adding client {} to chat {} as {role}", msg.user_uuid, msg.chat_uuid);
 
-        self.chat_rooms
-            .entry(msg.chat_uuid)
-            .or_default()
-            .insert((msg.user_uuid, msg.addr));
+        // The newest connection has seen the latest settings of the chat
+        match msg.default_ttl {
+            Some(default_ttl) => self.default_ttls.insert(msg.chat_uuid, default_ttl),
+            None => self.default_ttls.remove(&msg.chat_uuid),
+        };
+
+        let sessions = self.chat_rooms.entry(msg.chat_uuid).or_default();
+
+        // The newcomer gets the current roster right away, one entry per user
+        let mut roster: HashMap<Uuid, &Connection> = HashMap::new();
+        for session in sessions.iter() {
+            if let Some(connection) = &session.connection {
+                roster.insert(session.user_uuid, connection);
+            }
+        }
+        for connection in roster.into_values() {
+            msg.addr.do_send(WsMessage::new(Data::Connection(connection.clone())));
+        }
+
+        replay_undelivered(msg.chat_uuid, msg.user_uuid, msg.addr.clone(), self.offline_retention);
+
+        sessions.push(Session {
+            user_uuid: msg.user_uuid,
+            role,
+            addr: msg.addr,
+            connection: None,
+            last_typing: None,
+        });
     }
 }
 
@@ -166,21 +1493,297 @@ impl Handler<Disconnect> for ChatServer {
     fn handle(&mut self, msg: Disconnect, _: &mut Self::Context) {
         info!("Removing client {} from chat {}", msg.user_uuid, msg.chat_uuid);
 
-        if let Some(users) = self.chat_rooms.get_mut(&msg.chat_uuid) {
-            users.retain(|(uuid, _)| *uuid != msg.user_uuid);
+        let mut connection = None;
+
+        if let Some(sessions) = self.chat_rooms.get_mut(&msg.chat_uuid) {
+            if let Some(index) = sessions.iter().position(|session| session.addr == msg.addr) {
+                connection = sessions.remove(index).connection;
+            }
+
+            // Other sessions of the same user inherit the presence, so it can be announced once they leave too
+            for session in sessions
+                .iter_mut()
+                .filter(|session| session.user_uuid == msg.user_uuid && session.connection.is_none())
+            {
+                session.connection.clone_from(&connection);
+            }
+
+            if sessions.is_empty() {
+                self.chat_rooms.remove(&msg.chat_uuid);
+                self.default_ttls.remove(&msg.chat_uuid);
+            }
         }
+
+        self.announce_leave(msg.chat_uuid, msg.user_uuid, connection);
+    }
+}
+
+impl Handler<IsBanned> for ChatServer {
+    type Result = bool;
+
+    fn handle(&mut self, msg: IsBanned, _: &mut Self::Context) -> Self::Result {
+        self.rate_limits
+            .get(&msg.requester)
+            .is_some_and(|rate_limit| rate_limit.is_banned(Instant::now()))
     }
 }
 
 impl Handler<BroadcastMessage> for ChatServer {
     type Result = ();
 
-    fn handle(&mut self, msg: BroadcastMessage, _: &mut Self::Context) {
-        if let Some(users) = self.chat_rooms.get(&msg.chat_uuid) {
-            for (_, user) in users {
-                // For debugging purposes, you can log all recipients here
-                user.do_send(msg.message.clone());
+    fn handle(&mut self, msg: BroadcastMessage, ctx: &mut Self::Context) {
+        if !self.within_rate_limit(&msg) {
+            return;
+        }
+
+        let Some(sessions) = self.chat_rooms.get_mut(&msg.chat_uuid) else {
+            return;
+        };
+
+        match &msg.message.data {
+            Data::ChatMessage(chat_message) => {
+                let chat_message = chat_message.clone();
+
+                self.relay_chat_message(ctx, msg, &chat_message);
+
+                return;
+            }
+            Data::GroupKey(group_key) => {
+                // Handing out keys is writing as well: observers must not be able to swap the group key
+                if !can_write(sessions, msg.user_uuid) {
+                    warn!(
+                        "Dropping group key of client {} in chat {}: no write permission",
+                        msg.user_uuid, msg.chat_uuid
+                    );
+
+                    msg.addr
+                        .do_send(error_frame(ErrorCode::Forbidden, "No write permission", None));
+
+                    return;
+                }
+
+                // The session validated the recipient already
+                let Ok(for_user_uuid) = Uuid::parse_str(&group_key.for_user_id) else {
+                    return;
+                };
+
+                self.deliver(msg.chat_uuid, Some(&msg.addr), &msg.recipients, &msg.message);
+
+                store_group_key(msg.chat_uuid, for_user_uuid, msg.user_uuid, group_key);
+
+                return;
+            }
+            Data::Connection(connection) => {
+                // Keeping track of the roster, a client saying goodbye leaves it right away
+                let announced = match connection.status {
+                    ConnectionStatus::Disconnected => None,
+                    _ => Some(connection.clone()),
+                };
+
+                for session in sessions.iter_mut().filter(|session| session.user_uuid == msg.user_uuid) {
+                    session.connection.clone_from(&announced);
+                }
+            }
+            Data::Receipt(receipt) => {
+                let Ok(message_uuid) = Uuid::parse_str(&receipt.message_uuid) else {
+                    msg.addr
+                        .do_send(error_frame(ErrorCode::BadRequest, "Invalid message_uuid", None));
+
+                    return;
+                };
+
+                route_receipt(ctx, message_uuid, receipt.kind, msg);
+
+                return;
+            }
+            Data::EditMessage(EditMessage { message_uuid, .. })
+            | Data::DeleteMessage(DeleteMessage { message_uuid, .. })
+            | Data::Reaction(Reaction { message_uuid, .. }) => {
+                let Ok(message_uuid) = Uuid::parse_str(message_uuid) else {
+                    msg.addr
+                        .do_send(error_frame(ErrorCode::BadRequest, "Invalid message_uuid", None));
+
+                    return;
+                };
+
+                if matches!(msg.message.data, Data::Reaction(_)) {
+                    route_reaction(ctx, message_uuid, msg);
+                } else {
+                    route_amendment(ctx, sessions, message_uuid, msg);
+                }
+
+                return;
+            }
+            Data::Attachment(_) => {
+                route_attachment(ctx, sessions, msg);
+
+                return;
+            }
+            Data::Typing(typing) => {
+                self.relay_typing(&msg, typing.is_typing);
+
+                return;
+            }
+            Data::Ack(_) | Data::Error(_) | Data::Hello(_) | Data::Ping(_) | Data::Purged(_) => (),
+        }
+
+        self.deliver(msg.chat_uuid, Some(&msg.addr), &msg.recipients, &msg.message);
+    }
+}
+
+impl Handler<ChatChanged> for ChatServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: ChatChanged, _: &mut Self::Context) {
+        if !self.chat_rooms.contains_key(&msg.chat_uuid) {
+            return;
+        }
+
+        match msg.default_ttl {
+            Some(default_ttl) => self.default_ttls.insert(msg.chat_uuid, default_ttl),
+            None => self.default_ttls.remove(&msg.chat_uuid),
+        };
+    }
+}
+
+impl Handler<ChatDeleted> for ChatServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: ChatDeleted, _: &mut Self::Context) {
+        self.default_ttls.remove(&msg.chat_uuid);
+
+        // Disconnects of the closed sessions find the room gone already, so nobody gets told about them leaving
+        for session in self.chat_rooms.remove(&msg.chat_uuid).unwrap_or_default() {
+            session.addr.do_send(CloseSession {
+                reason: "Chat deleted".to_string(),
+            });
+        }
+    }
+}
+
+impl Handler<MessagesPurged> for ChatServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: MessagesPurged, _: &mut Self::Context) {
+        self.purge(msg.chat_uuid, &msg.message_uuids);
+    }
+}
+
+impl Handler<MemberChanged> for ChatServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: MemberChanged, _: &mut Self::Context) {
+        let mut connection = None;
+
+        if let Some(sessions) = self.chat_rooms.get_mut(&msg.chat_uuid) {
+            match msg.role {
+                Some(role) => {
+                    for session in sessions.iter_mut().filter(|session| session.user_uuid == msg.user_uuid) {
+                        session.role = role;
+                    }
+                }
+                None => sessions.retain(|session| {
+                    if session.user_uuid != msg.user_uuid {
+                        return true;
+                    }
+
+                    session.addr.do_send(CloseSession {
+                        reason: "Removed from this chat".to_string(),
+                    });
+                    connection = connection.take().or_else(|| session.connection.clone());
+
+                    false
+                }),
             }
         }
+
+        if msg.role.is_none() {
+            self.announce_leave(msg.chat_uuid, msg.user_uuid, connection);
+        }
+    }
+}
+
+#[cfg(test)]
+mod ws_actor_tests {
+    use super::{read_by_all, Recipients};
+    use crate::models::chat_member::item::ChatMember;
+    use chrono::{NaiveDateTime, TimeDelta};
+    use uuid::Uuid;
+
+    fn member(user_uuid: Uuid, last_read_date: Option<NaiveDateTime>) -> ChatMember {
+        ChatMember {
+            id: 1,
+            chat_id: 1,
+            user_uuid,
+            role: "member".to_string(),
+            creation_date: NaiveDateTime::default(),
+            modification_date: None,
+            last_read_message_uuid: None,
+            last_read_date,
+            last_delivered_message_uuid: None,
+            last_delivered_date: None,
+        }
+    }
+
+    #[test]
+    fn recipients_room() {
+        let user_uuid = Uuid::new_v4();
+
+        assert!(Recipients::Room.includes(user_uuid, true));
+        assert!(Recipients::Room.includes(user_uuid, false));
+    }
+
+    #[test]
+    fn recipients_others() {
+        let user_uuid = Uuid::new_v4();
+
+        assert!(!Recipients::Others.includes(user_uuid, true));
+        // Other sessions of the sending user still get it
+        assert!(Recipients::Others.includes(user_uuid, false));
+    }
+
+    #[test]
+    fn recipients_users() {
+        let (user_uuid, other_uuid) = (Uuid::new_v4(), Uuid::new_v4());
+        let recipients = Recipients::Users(vec![user_uuid]);
+
+        assert!(recipients.includes(user_uuid, false));
+        assert!(recipients.includes(user_uuid, true));
+        assert!(!recipients.includes(other_uuid, false));
+        assert!(!Recipients::Users(Vec::new()).includes(user_uuid, false));
+    }
+
+    #[test]
+    fn read_by_all_members() {
+        let (sender_uuid, first_uuid, second_uuid) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let sent_at = NaiveDateTime::parse_from_str("2023-10-01T12:34:56", "%Y-%m-%dT%H:%M:%S").unwrap();
+        let later = sent_at + TimeDelta::seconds(1);
+
+        // The sender never reads their own message, the first read alone doesn't burn it in a group
+        let members = vec![
+            member(sender_uuid, None),
+            member(first_uuid, Some(sent_at)),
+            member(second_uuid, None),
+        ];
+        assert!(!read_by_all(&members, sender_uuid, sent_at));
+
+        // Read pointers behind the message don't count either
+        let members = vec![
+            member(sender_uuid, None),
+            member(first_uuid, Some(sent_at)),
+            member(second_uuid, Some(sent_at - TimeDelta::seconds(1))),
+        ];
+        assert!(!read_by_all(&members, sender_uuid, sent_at));
+
+        let members = vec![
+            member(sender_uuid, None),
+            member(first_uuid, Some(sent_at)),
+            member(second_uuid, Some(later)),
+        ];
+        assert!(read_by_all(&members, sender_uuid, sent_at));
+
+        // A two member chat burns with the one read receipt
+        let members = vec![member(sender_uuid, None), member(first_uuid, Some(sent_at))];
+        assert!(read_by_all(&members, sender_uuid, sent_at));
     }
 }